@@ -0,0 +1,786 @@
+//! Command-line handling for the `datefmt` binary, modelled on coreutils
+//! `date`.
+
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, TimeZone, Utc};
+use datefmt::{parse_date, CompiledFormat, DataSource, Formatter, NamedFormat, Tz};
+
+const PROGRAM: &str = "datefmt";
+
+/// Environment variable naming ICU data to load when `--icu-data` is not
+/// given.
+const ICU_DATA_VAR: &str = "DATEFMT_ICU_DATA";
+
+/// Format used when no `+FORMAT` operand is given.
+const DEFAULT_FORMAT: &str = "%c";
+
+const USAGE: &str = "\
+Usage: datefmt [OPTION]... [+FORMAT]
+Display the current date and time in the given FORMAT.
+
+  -d, --date=STRING    display time described by STRING, not 'now'
+  -f, --file=DATEFILE  like --date; once for each line of DATEFILE, or of
+                         standard input if DATEFILE is '-'
+  -I[FMT], --iso-8601[=FMT]  output date/time in ISO 8601 format; FMT is
+                         'date' (the default), 'hours', 'minutes',
+                         'seconds' or 'ns' for the precision shown
+  -R, --rfc-email      output date and time in RFC 5322 format, e.g.
+                         'Mon, 14 Aug 2006 02:34:56 -0600'
+      --rfc-3339=FMT   output date/time in RFC 3339 format; FMT is
+                         'date', 'seconds' or 'ns' for the precision shown
+  -r, --reference=FILE display the last modification time of FILE; may be
+                         given more than once
+      --time=WORD      with -r, show the time of WORD instead of the
+                         modification: atime or access, mtime or modify,
+                         ctime or status, birth or creation
+      --prefix=TEMPLATE  with -r, start each line with TEMPLATE, where {}
+                         is the file name (default: '{}: ' for several files)
+  -u, --utc, --universal  print Coordinated Universal Time (UTC)
+      --tz=ZONE        print the time in the IANA time zone ZONE, such as
+                         Europe/Berlin (default: $TZ, then the system zone)
+      --explain=FORMAT  describe each conversion in FORMAT, show it for a
+                         sample date, and exit
+      --lint=FORMAT    report conversions in FORMAT that are unknown, not
+                         portable to other strftime implementations,
+                         ambiguous or likely mistakes, and exit with
+                         status 1 if there are any
+      --guess=EXAMPLE  list the formats that reproduce the date EXAMPLE,
+                         most likely first, and exit
+      --icu-data=PATH  load ICU locale data from PATH, a directory or a
+                         postcard blob written by icu4x-datagen, instead of
+                         the compiled data (default: $DATEFMT_ICU_DATA)
+      --help           display this help and exit
+      --version        output version information and exit
+
+FORMAT uses the conversions of date(1), e.g. '+%Y-%m-%d %H:%M:%S'.
+Without FORMAT, the locale's date and time representation (%c) is shown.
+The locale is taken from LC_ALL, LC_TIME or LANG.
+";
+
+/// Whether an option takes an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Argument {
+    None,
+    Required,
+    /// Attached to a short option (`-Ins`) or after `=` (`--iso-8601=ns`).
+    Optional,
+}
+
+struct OptionSpec {
+    long: &'static str,
+    short: Option<char>,
+    argument: Argument,
+}
+
+const OPTIONS: &[OptionSpec] = &[
+    OptionSpec {
+        long: "date",
+        short: Some('d'),
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "file",
+        short: Some('f'),
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "iso-8601",
+        short: Some('I'),
+        argument: Argument::Optional,
+    },
+    OptionSpec {
+        long: "rfc-email",
+        short: Some('R'),
+        argument: Argument::None,
+    },
+    OptionSpec {
+        long: "rfc-3339",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "reference",
+        short: Some('r'),
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "time",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "prefix",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "utc",
+        short: Some('u'),
+        argument: Argument::None,
+    },
+    OptionSpec {
+        long: "universal",
+        short: None,
+        argument: Argument::None,
+    },
+    OptionSpec {
+        long: "tz",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "explain",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "lint",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "guess",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "icu-data",
+        short: None,
+        argument: Argument::Required,
+    },
+    OptionSpec {
+        long: "help",
+        short: None,
+        argument: Argument::None,
+    },
+    OptionSpec {
+        long: "version",
+        short: None,
+        argument: Argument::None,
+    },
+];
+
+/// What the command line asks for.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Command {
+    Format(Box<Options>),
+    Help,
+    Version,
+}
+
+/// Settings for formatting, gathered from the command line.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Options {
+    /// The `+FORMAT` operand without its `+`.
+    pub format: Option<String>,
+    /// Format given with `--iso-8601`, `--rfc-email` or `--rfc-3339`.
+    pub named: Option<NamedFormat>,
+    /// Date string given with `--date`.
+    pub date: Option<String>,
+    /// File of date strings given with `--file`, where `-` is stdin.
+    pub file: Option<PathBuf>,
+    /// Files given with `--reference`, in order.
+    pub references: Vec<PathBuf>,
+    /// Which timestamp of the references to show.
+    pub time: FileTime,
+    /// Template given with `--prefix`.
+    pub prefix: Option<String>,
+    /// Zone given with `--utc` or `--tz`, whichever came last.
+    pub zone: Option<Tz>,
+    /// Format string given with `--explain`.
+    pub explain: Option<String>,
+    /// Format string given with `--lint`.
+    pub lint: Option<String>,
+    /// Example date given with `--guess`.
+    pub guess: Option<String>,
+    /// Path given with `--icu-data`.
+    pub icu_data: Option<PathBuf>,
+}
+
+/// A file timestamp selected with `--time`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum FileTime {
+    Access,
+    #[default]
+    Modification,
+    Change,
+    Birth,
+}
+
+impl FileTime {
+    fn from_word(word: &str) -> Option<Self> {
+        let time = match word {
+            "atime" | "access" | "use" => FileTime::Access,
+            "mtime" | "modify" | "modification" => FileTime::Modification,
+            "ctime" | "status" => FileTime::Change,
+            "birth" | "creation" => FileTime::Birth,
+            _ => return None,
+        };
+        Some(time)
+    }
+
+    /// Reads this timestamp of the file at `path`, following symbolic
+    /// links, with the precision the file system records.
+    fn of(self, path: &Path) -> io::Result<SystemTime> {
+        let metadata = fs::metadata(path)?;
+        match self {
+            FileTime::Access => metadata.accessed(),
+            FileTime::Modification => metadata.modified(),
+            FileTime::Change => status_change_time(&metadata),
+            FileTime::Birth => metadata.created(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn status_change_time(metadata: &fs::Metadata) -> io::Result<SystemTime> {
+    use std::os::unix::fs::MetadataExt;
+
+    let nanoseconds = Duration::from_nanos(metadata.ctime_nsec().unsigned_abs());
+    let seconds = Duration::from_secs(metadata.ctime().unsigned_abs());
+    Ok(if metadata.ctime() < 0 {
+        UNIX_EPOCH - seconds + nanoseconds
+    } else {
+        UNIX_EPOCH + seconds + nanoseconds
+    })
+}
+
+#[cfg(not(unix))]
+fn status_change_time(_metadata: &fs::Metadata) -> io::Result<SystemTime> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "status change time is not available on this platform",
+    ))
+}
+
+/// A failure reported on stderr. Usage errors also point to `--help`.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Error {
+    message: String,
+    usage: bool,
+}
+
+impl Error {
+    fn usage(message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            usage: true,
+        }
+    }
+
+    fn fatal(message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            usage: false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Runs the binary with `args`, which exclude the program name.
+pub(crate) fn run(args: impl IntoIterator<Item = OsString>) -> ExitCode {
+    match try_run(args) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("{PROGRAM}: {error}");
+            if error.usage {
+                eprintln!("Try '{PROGRAM} --help' for more information.");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn try_run(args: impl IntoIterator<Item = OsString>) -> Result<ExitCode, Error> {
+    let args = args
+        .into_iter()
+        .map(|arg| {
+            arg.into_string().map_err(|arg| {
+                Error::usage(format!("invalid argument '{}'", arg.to_string_lossy()))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let code = match parse_args(args)? {
+        Command::Help => write!(stdout, "{USAGE}")
+            .map(|()| ExitCode::SUCCESS)
+            .map_err(write_error)?,
+        Command::Version => writeln!(stdout, "{PROGRAM} {}", env!("CARGO_PKG_VERSION"))
+            .map(|()| ExitCode::SUCCESS)
+            .map_err(write_error)?,
+        Command::Format(options) => print_dates(&options, &mut stdout)?,
+    };
+    stdout.flush().map_err(write_error)?;
+    Ok(code)
+}
+
+fn write_error(error: io::Error) -> Error {
+    Error::fatal(format!("write error: {error}"))
+}
+
+/// Prints the dates `options` ask for: the current one, the one given with
+/// `--date`, one per line of the `--file`, or one per `--reference`.
+fn print_dates(options: &Options, stdout: &mut impl Write) -> Result<ExitCode, Error> {
+    let formatter = formatter(options)?;
+    if let Some(format_string) = &options.explain {
+        write!(stdout, "{}", formatter.explain(format_string)).map_err(write_error)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(format_string) = &options.lint {
+        let lints = datefmt::lint(format_string);
+        for lint in &lints {
+            writeln!(stdout, "{lint}").map_err(write_error)?;
+        }
+        return Ok(if lints.is_empty() {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
+    }
+    if let Some(example) = &options.guess {
+        let candidates = formatter.infer_format(example);
+        if candidates.is_empty() {
+            return Err(Error::fatal(format!(
+                "cannot infer a format for '{example}'"
+            )));
+        }
+        for candidate in candidates {
+            writeln!(
+                stdout,
+                "{:5.1}%  +{}",
+                candidate.confidence * 100.0,
+                candidate.format
+            )
+            .map_err(write_error)?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    let plan = plan(options);
+    match options.zone.or_else(datefmt::zone_from_env) {
+        Some(zone) => print_dates_in(
+            options,
+            &plan,
+            &formatter,
+            Utc::now().with_timezone(&zone),
+            stdout,
+        ),
+        // A TZ the tz database does not know, such as a POSIX rule, keeps
+        // the abbreviation and offset of its standard time.
+        None => {
+            match env::var("TZ") {
+                Ok(tz) => match datefmt::parse_posix_zone(&tz) {
+                    Some(zone) => print_dates_in(
+                        options,
+                        &plan,
+                        &formatter,
+                        Utc::now().with_timezone(&zone),
+                        stdout,
+                    ),
+                    None => {
+                        eprintln!("{PROGRAM}: warning: unknown time zone '{tz}', using the local time zone");
+                        print_dates_in(options, &plan, &formatter, Local::now(), stdout)
+                    }
+                },
+                Err(_) => print_dates_in(options, &plan, &formatter, Local::now(), stdout),
+            }
+        }
+    }
+}
+
+/// Prints the dates `options` ask for in the time zone of `now`.
+fn print_dates_in<Z: TimeZone>(
+    options: &Options,
+    plan: &CompiledFormat,
+    formatter: &Formatter,
+    now: DateTime<Z>,
+    stdout: &mut impl Write,
+) -> Result<ExitCode, Error>
+where
+    Z::Offset: fmt::Display,
+{
+    let batch = Batch {
+        now: &now,
+        plan,
+        formatter,
+    };
+    if !options.references.is_empty() {
+        let prefix = match &options.prefix {
+            Some(prefix) => Some(prefix.as_str()),
+            None if options.references.len() > 1 => Some("{}: "),
+            None => None,
+        };
+        let all_valid = batch.format_files(
+            &options.references,
+            options.time,
+            prefix,
+            stdout,
+            &mut io::stderr(),
+        )?;
+        return Ok(exit_code(all_valid));
+    }
+    let Some(path) = &options.file else {
+        let datetime = match &options.date {
+            Some(input) => parse_date(input, &now)
+                .map_err(|error| Error::fatal(format!("invalid date '{input}': {error}")))?,
+            None => now.clone(),
+        };
+        writeln!(stdout, "{}", formatter.display(plan, datetime)).map_err(write_error)?;
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let name = path.display().to_string();
+    let all_valid = if name == "-" {
+        batch.format_lines(io::stdin().lock(), &name, stdout, &mut io::stderr())?
+    } else {
+        let file = File::open(path).map_err(|error| Error::fatal(format!("{name}: {error}")))?;
+        batch.format_lines(BufReader::new(file), &name, stdout, &mut io::stderr())?
+    };
+    Ok(exit_code(all_valid))
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// What every date of a `--file` or of the `--reference` files is
+/// formatted with.
+struct Batch<'a, Z: TimeZone> {
+    now: &'a DateTime<Z>,
+    plan: &'a CompiledFormat,
+    formatter: &'a Formatter,
+}
+
+impl<Z: TimeZone> Batch<'_, Z>
+where
+    Z::Offset: fmt::Display,
+{
+    /// Formats the date on each line of `input`, which is called `name` in
+    /// messages.
+    ///
+    /// Lines that are not a valid date are reported to `errors` and
+    /// skipped. Returns whether every line was valid.
+    fn format_lines(
+        &self,
+        mut input: impl BufRead,
+        name: &str,
+        output: &mut impl Write,
+        errors: &mut impl Write,
+    ) -> Result<bool, Error> {
+        let mut all_valid = true;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = input
+                .read_until(b'\n', &mut line)
+                .map_err(|error| Error::fatal(format!("{name}: {error}")))?;
+            if read == 0 {
+                return Ok(all_valid);
+            }
+            // Files written on Windows end their lines with "\r\n".
+            if line.ends_with(b"\r\n") {
+                line.truncate(line.len() - 2);
+            } else if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            let parsed = match std::str::from_utf8(&line) {
+                Ok(text) => parse_date(text, self.now).map_err(|error| error.to_string()),
+                Err(_) => Err("not valid UTF-8".to_owned()),
+            };
+            match parsed {
+                Ok(datetime) => writeln!(output, "{}", self.formatter.display(self.plan, datetime))
+                    .map_err(write_error)?,
+                Err(message) => {
+                    all_valid = false;
+                    // Failing to report a bad line is no reason to stop the batch.
+                    let _ = writeln!(
+                        errors,
+                        "{PROGRAM}: invalid date '{}': {message}",
+                        String::from_utf8_lossy(&line)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Formats the `time` of each file in `paths`, starting each line with
+    /// `prefix` where `{}` stands for the file name.
+    ///
+    /// Files whose time cannot be read are reported to `errors` and
+    /// skipped. Returns whether every time was read.
+    fn format_files(
+        &self,
+        paths: &[PathBuf],
+        time: FileTime,
+        prefix: Option<&str>,
+        output: &mut impl Write,
+        errors: &mut impl Write,
+    ) -> Result<bool, Error> {
+        let mut all_valid = true;
+        for path in paths {
+            let name = path.display().to_string();
+            match time.of(path) {
+                Ok(time) => {
+                    let datetime = DateTime::<Utc>::from(time).with_timezone(&self.now.timezone());
+                    let prefix = prefix.map(|prefix| prefix.replace("{}", &name));
+                    writeln!(
+                        output,
+                        "{}{}",
+                        prefix.unwrap_or_default(),
+                        self.formatter.display(self.plan, datetime)
+                    )
+                    .map_err(write_error)?;
+                }
+                Err(error) => {
+                    all_valid = false;
+                    let _ = writeln!(errors, "{PROGRAM}: {name}: {error}");
+                }
+            }
+        }
+        Ok(all_valid)
+    }
+}
+
+/// Parses the command line the way `getopt_long` does: options and
+/// operands may be mixed, long options may be abbreviated, and `--` ends
+/// the options.
+pub(crate) fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, Error> {
+    let mut options = Options::default();
+    let mut operands = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            operands.extend(args.by_ref());
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_owned())),
+                None => (long, None),
+            };
+            let spec = long_option(name)?;
+            let value = match (spec.argument, value) {
+                (Argument::None, Some(_)) => {
+                    return Err(Error::usage(format!(
+                        "option '--{}' doesn't allow an argument",
+                        spec.long
+                    )))
+                }
+                (Argument::Required, None) => Some(args.next().ok_or_else(|| {
+                    Error::usage(format!("option '--{}' requires an argument", spec.long))
+                })?),
+                (_, value) => value,
+            };
+            if let Some(command) = apply(&mut options, spec, value)? {
+                return Ok(command);
+            }
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            for (index, c) in arg.char_indices().skip(1) {
+                let spec = short_option(c)?;
+                let rest = &arg[index + c.len_utf8()..];
+                let value = match spec.argument {
+                    Argument::None => None,
+                    Argument::Optional | Argument::Required if !rest.is_empty() => {
+                        Some(rest.to_owned())
+                    }
+                    Argument::Required => Some(args.next().ok_or_else(|| {
+                        Error::usage(format!("option requires an argument -- '{c}'"))
+                    })?),
+                    Argument::Optional => None,
+                };
+                let takes_rest = value.is_some();
+                if let Some(command) = apply(&mut options, spec, value)? {
+                    return Ok(command);
+                }
+                if takes_rest {
+                    break;
+                }
+            }
+        } else {
+            operands.push(arg);
+        }
+    }
+
+    for operand in operands {
+        match operand.strip_prefix('+') {
+            Some(_) if options.named.is_some() => return Err(multiple_formats()),
+            Some(format) if options.format.is_none() => options.format = Some(format.to_owned()),
+            Some(_) => return Err(Error::usage(format!("extra operand '{operand}'"))),
+            None => {
+                return Err(Error::usage(format!(
+                "the argument '{operand}' lacks a leading '+'; setting the date is not supported"
+            )))
+            }
+        }
+    }
+    let date_sources = [
+        options.date.is_some(),
+        options.file.is_some(),
+        !options.references.is_empty(),
+    ];
+    if date_sources.into_iter().filter(|&given| given).count() > 1 {
+        return Err(Error::usage(
+            "the options to specify dates for printing are mutually exclusive",
+        ));
+    }
+    Ok(Command::Format(Box::new(options)))
+}
+
+/// Finds the option `--name` abbreviates, preferring an exact match.
+fn long_option(name: &str) -> Result<&'static OptionSpec, Error> {
+    if let Some(spec) = OPTIONS.iter().find(|spec| spec.long == name) {
+        return Ok(spec);
+    }
+    let candidates: Vec<_> = OPTIONS
+        .iter()
+        .filter(|spec| !name.is_empty() && spec.long.starts_with(name))
+        .collect();
+    match candidates[..] {
+        [spec] => Ok(spec),
+        [] => Err(Error::usage(format!("unrecognized option '--{name}'"))),
+        _ => Err(Error::usage(format!(
+            "option '--{name}' is ambiguous; possibilities:{}",
+            candidates
+                .iter()
+                .map(|spec| format!(" '--{}'", spec.long))
+                .collect::<String>()
+        ))),
+    }
+}
+
+fn short_option(c: char) -> Result<&'static OptionSpec, Error> {
+    OPTIONS
+        .iter()
+        .find(|spec| spec.short == Some(c))
+        .ok_or_else(|| Error::usage(format!("invalid option -- '{c}'")))
+}
+
+/// Records one option, or returns the command it stands for on its own.
+fn apply(
+    options: &mut Options,
+    spec: &OptionSpec,
+    value: Option<String>,
+) -> Result<Option<Command>, Error> {
+    match spec.long {
+        "date" => options.date = value,
+        "file" => options.file = value.map(PathBuf::from),
+        "iso-8601" => {
+            let format = match value {
+                Some(word) => NamedFormat::iso_8601(&word).ok_or_else(|| {
+                    invalid_precision(&word, "iso-8601", NamedFormat::iso_8601_precisions())
+                })?,
+                None => NamedFormat::IsoDate,
+            };
+            set_named(options, format)?;
+        }
+        "rfc-email" => set_named(options, NamedFormat::RfcEmail)?,
+        "rfc-3339" => {
+            let word = value.unwrap_or_default();
+            let format = NamedFormat::rfc_3339(&word).ok_or_else(|| {
+                invalid_precision(&word, "rfc-3339", NamedFormat::rfc_3339_precisions())
+            })?;
+            set_named(options, format)?;
+        }
+        "reference" => options.references.extend(value.map(PathBuf::from)),
+        "time" => {
+            let word = value.unwrap_or_default();
+            options.time = FileTime::from_word(&word).ok_or_else(|| {
+                Error::usage(format!(
+                    "invalid argument '{word}' for '--time'; valid arguments are \
+                     'atime', 'access', 'mtime', 'modify', 'ctime', 'status', \
+                     'birth' and 'creation'"
+                ))
+            })?;
+        }
+        "prefix" => options.prefix = value,
+        "utc" | "universal" => options.zone = Some(Tz::UTC),
+        "tz" => {
+            let name = value.unwrap_or_default();
+            options.zone = Some(
+                datefmt::parse_zone(&name)
+                    .ok_or_else(|| Error::usage(format!("invalid time zone '{name}'")))?,
+            );
+        }
+        "explain" => options.explain = value,
+        "lint" => options.lint = value,
+        "guess" => options.guess = value,
+        "icu-data" => options.icu_data = value.map(PathBuf::from),
+        "help" => return Ok(Some(Command::Help)),
+        "version" => return Ok(Some(Command::Version)),
+        name => unreachable!("option '--{name}' is not handled"),
+    }
+    Ok(None)
+}
+
+/// Records a named output format; only one may be given.
+fn set_named(options: &mut Options, format: NamedFormat) -> Result<(), Error> {
+    if options.named.replace(format).is_some() {
+        return Err(multiple_formats());
+    }
+    Ok(())
+}
+
+fn multiple_formats() -> Error {
+    Error::usage("multiple output formats specified")
+}
+
+fn invalid_precision(word: &str, option: &str, valid: impl Iterator<Item = &'static str>) -> Error {
+    let valid: Vec<_> = valid.map(|word| format!("'{word}'")).collect();
+    let (last, rest) = valid.split_last().expect("every option has precisions");
+    Error::usage(format!(
+        "invalid argument '{word}' for '--{option}'; valid arguments are {} and {last}",
+        rest.join(", ")
+    ))
+}
+
+/// Builds the formatter for the environment's locale and the requested
+/// locale data.
+fn formatter(options: &Options) -> Result<Formatter, Error> {
+    if options.named.is_some() {
+        // Named formats always use the C locale's English names.
+        return Ok(Formatter::default());
+    }
+    let formatter = Formatter::new(datefmt::locale_from_env());
+    let icu_data = options.icu_data.clone().or_else(|| {
+        env::var_os(ICU_DATA_VAR)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    });
+    let Some(path) = icu_data else {
+        return Ok(formatter);
+    };
+    let data = DataSource::open(&path).map_err(|error| {
+        Error::fatal(format!(
+            "cannot load ICU data from '{}': {error}",
+            path.display()
+        ))
+    })?;
+    Ok(formatter.with_data(data))
+}
+
+/// The requested format, parsed once for every date printed.
+fn plan(options: &Options) -> CompiledFormat {
+    if let Some(named) = options.named {
+        return named.compile();
+    }
+    CompiledFormat::parse(options.format.as_deref().unwrap_or(DEFAULT_FORMAT))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,332 @@
+use chrono::{FixedOffset, TimeZone, Utc};
+
+use super::{parse_args, plan, print_dates_in, Batch, Command, Error, FileTime, Options};
+use datefmt::{Formatter, NamedFormat, Tz};
+
+fn parse(args: &[&str]) -> Result<Command, Error> {
+    parse_args(args.iter().map(|arg| arg.to_string()))
+}
+
+fn options(args: &[&str]) -> Options {
+    match parse(args) {
+        Ok(Command::Format(options)) => *options,
+        result => panic!("{args:?} parsed to {result:?}"),
+    }
+}
+
+#[test]
+fn format_operand() {
+    assert_eq!(options(&[]).format, None);
+    assert_eq!(options(&["+%Y-%m-%d"]).format.as_deref(), Some("%Y-%m-%d"));
+    assert_eq!(options(&["--", "+%H"]).format.as_deref(), Some("%H"));
+    assert_eq!(options(&["+"]).format.as_deref(), Some(""));
+}
+
+#[test]
+fn bad_operands_are_usage_errors() {
+    let error = parse(&["+%Y", "+%m"]).unwrap_err();
+    assert_eq!(error, Error::usage("extra operand '+%m'"));
+    assert!(parse(&["12251200"]).unwrap_err().usage);
+    assert!(parse(&["--", "-x"]).unwrap_err().usage);
+}
+
+#[test]
+fn long_options() {
+    let expected = Some("/data".into());
+    assert_eq!(options(&["--icu-data", "/data"]).icu_data, expected);
+    assert_eq!(options(&["--icu-data=/data"]).icu_data, expected);
+    assert_eq!(options(&["+%c", "--icu=/data"]).icu_data, expected);
+    assert_eq!(parse(&["--he"]), Ok(Command::Help));
+    assert_eq!(parse(&["--version", "--bogus"]), Ok(Command::Version));
+}
+
+#[test]
+fn date_option() {
+    let expected = Some("next friday".to_owned());
+    assert_eq!(options(&["-d", "next friday"]).date, expected);
+    assert_eq!(options(&["-dnext friday"]).date, expected);
+    assert_eq!(options(&["--date=next friday", "+%F"]).date, expected);
+    assert_eq!(
+        parse(&["-d"]),
+        Err(Error::usage("option requires an argument -- 'd'"))
+    );
+}
+
+#[test]
+fn file_option() {
+    assert_eq!(options(&["-f", "-"]).file, Some("-".into()));
+    assert_eq!(
+        options(&["--file=dates.txt"]).file,
+        Some("dates.txt".into())
+    );
+    assert!(parse(&["-f", "dates.txt", "-d", "now"]).unwrap_err().usage);
+}
+
+#[test]
+fn reference_options() {
+    let parsed = options(&["-r", "a", "--reference=b", "--time=ctime", "--prefix={}\t"]);
+    assert_eq!(parsed.references, ["a", "b"].map(std::path::PathBuf::from));
+    assert_eq!(parsed.time, FileTime::Change);
+    assert_eq!(parsed.prefix.as_deref(), Some("{}\t"));
+    assert_eq!(options(&["-r", "a"]).time, FileTime::Modification);
+    assert_eq!(options(&["--time=birth"]).time, FileTime::Birth);
+    assert!(parse(&["--time=never"]).unwrap_err().usage);
+    assert!(parse(&["-r", "a", "-d", "now"]).unwrap_err().usage);
+}
+
+#[test]
+fn zone_options() {
+    assert_eq!(options(&[]).zone, None);
+    assert_eq!(options(&["-u"]).zone, Some(Tz::UTC));
+    assert_eq!(options(&["--universal"]).zone, Some(Tz::UTC));
+    let berlin = Some(Tz::Europe__Berlin);
+    assert_eq!(options(&["--tz", "Europe/Berlin"]).zone, berlin);
+    assert_eq!(options(&["-u", "--tz=:Europe/Berlin"]).zone, berlin);
+    assert_eq!(options(&["--tz=Europe/Berlin", "-u"]).zone, Some(Tz::UTC));
+    assert_eq!(
+        parse(&["--tz=Mars/Olympus"]),
+        Err(Error::usage("invalid time zone 'Mars/Olympus'"))
+    );
+}
+
+#[test]
+fn dates_are_shown_in_the_zone_of_now() {
+    let now = Tz::Europe__Berlin
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    let formatter = Formatter::default();
+    let cases = [
+        (&["+%F %T %Z %z"][..], "2024-12-14 15:04:05 CET +0100\n"),
+        (
+            &["-d", "2024-07-01 12:00 UTC", "+%T %Z %:z"],
+            "14:00:00 CEST +02:00\n",
+        ),
+        (&["-d", "2024-07-01 12:00", "+%T %Z"], "12:00:00 CEST\n"),
+    ];
+    for (args, expected) in cases {
+        let options = options(args);
+        let mut output = Vec::new();
+        print_dates_in(&options, &plan(&options), &formatter, now, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected, "{args:?}");
+    }
+}
+
+#[test]
+fn dates_are_shown_in_a_posix_tz() {
+    let formatter = Formatter::default();
+    let cases = [
+        ("UTC0", "2024-12-14 14:04:05 UTC +0000\n"),
+        ("EST5EDT,M3.2.0,M11.1.0", "2024-12-14 09:04:05 EST -0500\n"),
+        ("Foo/Bar", "2024-12-14 14:04:05 Foo +0000\n"),
+    ];
+    for (tz, expected) in cases {
+        let zone = datefmt::parse_posix_zone(tz).unwrap();
+        let now = Utc
+            .with_ymd_and_hms(2024, 12, 14, 14, 4, 5)
+            .unwrap()
+            .with_timezone(&zone);
+        let options = options(&["+%F %T %Z %z"]);
+        let mut output = Vec::new();
+        print_dates_in(&options, &plan(&options), &formatter, now, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected, "{tz}");
+    }
+}
+
+#[test]
+fn named_format_options() {
+    let cases = [
+        (&["-I"][..], NamedFormat::IsoDate),
+        (&["-Ins"], NamedFormat::IsoNanoseconds),
+        (&["-uIh"], NamedFormat::IsoHours),
+        (&["--iso-8601"], NamedFormat::IsoDate),
+        (&["--iso=minutes"], NamedFormat::IsoMinutes),
+        (&["-R"], NamedFormat::RfcEmail),
+        (&["--rfc-e"], NamedFormat::RfcEmail),
+        (&["--rfc-3339", "seconds"], NamedFormat::Rfc3339Seconds),
+        (&["--rfc-3339=d"], NamedFormat::Rfc3339Date),
+    ];
+    for (args, expected) in cases {
+        assert_eq!(options(args).named, Some(expected), "{args:?}");
+    }
+    // An optional argument is never taken from the next word.
+    assert!(parse(&["--iso-8601", "seconds"]).unwrap_err().usage);
+
+    let multiple = Err(Error::usage("multiple output formats specified"));
+    assert_eq!(parse(&["-I", "-R"]), multiple);
+    assert_eq!(parse(&["-Is", "-Is"]), multiple);
+    assert_eq!(parse(&["+%F", "-I"]), multiple);
+    assert_eq!(
+        parse(&["-Ix"]),
+        Err(Error::usage(
+            "invalid argument 'x' for '--iso-8601'; valid arguments are \
+             'hours', 'minutes', 'date', 'seconds' and 'ns'"
+        ))
+    );
+    assert!(parse(&["--rfc-3339=minutes"]).unwrap_err().usage);
+}
+
+#[test]
+fn named_formats_ignore_the_locale() {
+    let datetime = FixedOffset::east_opt(-12600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    let named = options(&["-R"]);
+    let formatter = super::formatter(&named).unwrap();
+    assert_eq!(
+        formatter.render(&plan(&named), datetime),
+        "Sat, 14 Dec 2024 15:04:05 -0330"
+    );
+}
+
+#[test]
+fn explain_option() {
+    assert_eq!(options(&["--explain", "%F"]).explain.as_deref(), Some("%F"));
+    assert_eq!(
+        options(&["--expl=%T", "+%F"]).explain.as_deref(),
+        Some("%T")
+    );
+    assert_eq!(options(&[]).explain, None);
+}
+
+#[test]
+fn lint_option() {
+    assert_eq!(options(&["--lint", "%P"]).lint.as_deref(), Some("%P"));
+    assert_eq!(options(&["--lint=%q"]).lint.as_deref(), Some("%q"));
+    assert_eq!(options(&[]).lint, None);
+}
+
+#[test]
+fn guess_option() {
+    assert_eq!(
+        options(&["--guess", "2024-03-05"]).guess.as_deref(),
+        Some("2024-03-05")
+    );
+    assert_eq!(options(&[]).guess, None);
+}
+
+#[test]
+fn bad_options_are_usage_errors() {
+    let cases = [
+        (&["--bogus"][..], "unrecognized option '--bogus'"),
+        (&["--icu-data"], "option '--icu-data' requires an argument"),
+        (&["--help=x"], "option '--help' doesn't allow an argument"),
+        (&["-q"], "invalid option -- 'q'"),
+    ];
+    for (args, message) in cases {
+        assert_eq!(parse(args), Err(Error::usage(message)), "{args:?}");
+    }
+}
+
+#[test]
+fn renders_format_or_locale_default() {
+    let datetime = FixedOffset::east_opt(3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    let formatter = Formatter::new("fr-FR".parse().unwrap());
+    assert_eq!(
+        formatter.render(&plan(&options(&["+%A %d %B %Y"])), datetime),
+        "samedi 14 décembre 2024"
+    );
+    assert_eq!(
+        formatter.render(&plan(&options(&[])), datetime),
+        "14/12/2024 15:04:05"
+    );
+}
+
+#[test]
+fn batch_continues_past_bad_lines() {
+    let now = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    let formatter = Formatter::default();
+    let plan = plan(&options(&["+%F %T"]));
+    let batch = Batch {
+        now: &now,
+        plan: &plan,
+        formatter: &formatter,
+    };
+    let input = "2024-01-02 03:04:05\nbogus\n\n@0\nyesterday";
+    let (mut output, mut errors) = (Vec::new(), Vec::new());
+    let all_valid = batch
+        .format_lines(input.as_bytes(), "-", &mut output, &mut errors)
+        .unwrap();
+    assert!(!all_valid);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "2024-01-02 03:04:05\n2024-12-14 00:00:00\n1970-01-01 00:00:00\n2024-12-13 15:04:05\n"
+    );
+    assert_eq!(
+        String::from_utf8(errors).unwrap(),
+        "datefmt: invalid date 'bogus': cannot understand 'bogus' at byte 0\n"
+    );
+
+    let (mut output, mut errors) = (Vec::new(), Vec::new());
+    assert!(batch
+        .format_lines(&b"@86400\n"[..], "-", &mut output, &mut errors)
+        .unwrap());
+    assert_eq!(output, b"1970-01-02 00:00:00\n");
+
+    // Lines ending in "\r\n" lose the "\r" too, in errors as well.
+    let (mut output, mut errors) = (Vec::new(), Vec::new());
+    assert!(!batch
+        .format_lines(&b"@0\r\nbogus\r\n@86400"[..], "-", &mut output, &mut errors)
+        .unwrap());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "1970-01-01 00:00:00\n1970-01-02 00:00:00\n"
+    );
+    assert_eq!(
+        String::from_utf8(errors).unwrap(),
+        "datefmt: invalid date 'bogus': cannot understand 'bogus' at byte 0\n"
+    );
+}
+
+#[test]
+fn reference_times_keep_nanoseconds() {
+    let dir = std::env::temp_dir().join(format!("datefmt-reference-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let modified = std::time::UNIX_EPOCH + std::time::Duration::new(1_700_000_000, 123_456_789);
+    let paths = [dir.join("a"), dir.join("missing"), dir.join("b")];
+    for path in [&paths[0], &paths[2]] {
+        let file = std::fs::File::create(path).unwrap();
+        file.set_modified(modified).unwrap();
+    }
+
+    let now = FixedOffset::east_opt(3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    let formatter = Formatter::default();
+    let plan = plan(&options(&["+%F %T.%N %z"]));
+    let batch = Batch {
+        now: &now,
+        plan: &plan,
+        formatter: &formatter,
+    };
+    let (mut output, mut errors) = (Vec::new(), Vec::new());
+    let all_valid = batch
+        .format_files(
+            &paths,
+            FileTime::Modification,
+            Some("[{}] "),
+            &mut output,
+            &mut errors,
+        )
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!all_valid);
+    let (a, b) = (paths[0].display(), paths[2].display());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        format!(
+            "[{a}] 2023-11-14 23:13:20.123456789 +0100\n\
+             [{b}] 2023-11-14 23:13:20.123456789 +0100\n"
+        )
+    );
+    let errors = String::from_utf8(errors).unwrap();
+    assert!(errors.starts_with(&format!("datefmt: {}: ", paths[1].display())));
+}
@@ -0,0 +1,671 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use core::fmt;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::directive::{self, Directive, Item, Kind, Modifier};
+use crate::display::FormatDisplay;
+use crate::error::FormatError;
+use crate::formattable::{self, FieldVisitor, Formattable};
+use crate::formatter::Formatter;
+use crate::output::{
+    digit_count, write_number, write_padded_number, write_repeated, write_text, Case, Padding,
+};
+
+/// A format string parsed once into literal and directive items.
+///
+/// Parsing splits the string into sections and resolves flags, widths and
+/// specifiers up front, so [`render`](Self::render) only looks up values.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use datefmt::CompiledFormat;
+///
+/// let plan = CompiledFormat::parse("%d/%m/%Y");
+/// let datetime = FixedOffset::east_opt(0)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+///     .unwrap();
+/// assert_eq!(plan.render(datetime), "14/12/2024");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledFormat {
+    items: Vec<Item>,
+}
+
+impl CompiledFormat {
+    /// Parses `format_string` into a reusable plan.
+    ///
+    /// Sections that are not a valid conversion become literal text, as
+    /// [`format`](crate::format) has always done. Use
+    /// [`try_parse`](Self::try_parse) to reject them instead.
+    pub fn parse(format_string: &str) -> Self {
+        Self::parse_sections(format_string, false).expect("lenient parsing does not fail")
+    }
+
+    /// Parses `format_string`, rejecting anything that is not a valid
+    /// conversion.
+    pub fn try_parse(format_string: &str) -> Result<Self, FormatError> {
+        Self::parse_sections(format_string, true)
+    }
+
+    fn parse_sections(format_string: &str, strict: bool) -> Result<Self, FormatError> {
+        let mut items = Vec::new();
+        for (offset, section) in directive::partition_format_string_into_sections(format_string) {
+            let last = offset + section.len() == format_string.len();
+            directive::parse_section(section, offset, last, strict, &mut items)?;
+        }
+        Ok(CompiledFormat { items })
+    }
+
+    /// The parsed items, in output order.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Renders `datetime` according to this plan, with English names.
+    ///
+    /// `%Z` prints the value's [zone name](Formattable::zone_name), which
+    /// is the zone abbreviation for a [`chrono_tz::Tz`] and the numeric
+    /// offset for a [`FixedOffset`](chrono::FixedOffset). Directives that
+    /// cannot be rendered produce no output. Use [`Formatter::render`] for
+    /// other locales.
+    pub fn render<T: Formattable>(&self, datetime: T) -> String {
+        Formatter::shared_default().render(self, datetime)
+    }
+
+    /// Renders `datetime` according to this plan, with English names,
+    /// failing on the first directive that cannot be rendered.
+    pub fn try_render<T: Formattable>(&self, datetime: T) -> Result<String, FormatError> {
+        Formatter::shared_default().try_render(self, datetime)
+    }
+
+    /// Pairs `datetime` with this plan for use with `write!` and
+    /// `format!`, rendering with English names. See [`FormatDisplay`].
+    pub fn display<T: Formattable>(&self, datetime: T) -> FormatDisplay<'_, T> {
+        Formatter::shared_default().display(self, datetime)
+    }
+
+    /// Renders with the names of `formatter`'s locale. When `strict` is
+    /// false, directives that fail are skipped instead of reported.
+    pub(crate) fn render_with<T: Formattable + ?Sized>(
+        &self,
+        formatter: &Formatter,
+        datetime: &T,
+        strict: bool,
+    ) -> Result<String, FormatError> {
+        let mut rendered = String::new();
+        match self.render_into(formatter, datetime, strict, &mut rendered) {
+            Ok(()) => Ok(rendered),
+            Err(RenderError::Format(error)) => Err(error),
+            Err(RenderError::Write(_)) => unreachable!("writing to a String does not fail"),
+        }
+    }
+
+    /// Renders into `out` as [`render_with`](Self::render_with) does,
+    /// writing each field as it is produced.
+    pub(crate) fn render_into<T: Formattable + ?Sized, W: fmt::Write>(
+        &self,
+        formatter: &Formatter,
+        datetime: &T,
+        strict: bool,
+        out: &mut W,
+    ) -> Result<(), RenderError> {
+        datetime.with_fields(Render {
+            plan: self,
+            formatter,
+            strict,
+            out,
+        })
+    }
+
+    /// Renders the items of this plan with the fields of `datetime`.
+    fn render_fields<T: Formattable + ?Sized, W: fmt::Write>(
+        &self,
+        formatter: &Formatter,
+        datetime: &T,
+        strict: bool,
+        out: &mut W,
+    ) -> Result<(), RenderError> {
+        for item in &self.items {
+            match item {
+                Item::Literal(text) => out.write_str(text)?,
+                Item::Directive(directive) => {
+                    match render_directive(directive, formatter, datetime, strict, out) {
+                        Err(RenderError::Format(error)) if strict => return Err(error.into()),
+                        Err(RenderError::Format(_)) => (),
+                        result => result?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a plan with the fields of the value
+/// [`with_fields`](Formattable::with_fields) hands it.
+struct Render<'a, W> {
+    plan: &'a CompiledFormat,
+    formatter: &'a Formatter,
+    strict: bool,
+    out: &'a mut W,
+}
+
+impl<W: fmt::Write> FieldVisitor for Render<'_, W> {
+    type Output = Result<(), RenderError>;
+
+    fn visit<T: Formattable + ?Sized>(self, datetime: &T) -> Self::Output {
+        self.plan
+            .render_fields(self.formatter, datetime, self.strict, self.out)
+    }
+}
+
+/// Why rendering into a sink stopped.
+#[derive(Debug)]
+pub(crate) enum RenderError {
+    /// A directive could not be rendered.
+    Format(FormatError),
+    /// The sink failed.
+    Write(fmt::Error),
+}
+
+impl From<FormatError> for RenderError {
+    fn from(error: FormatError) -> Self {
+        RenderError::Format(error)
+    }
+}
+
+impl From<fmt::Error> for RenderError {
+    fn from(error: fmt::Error) -> Self {
+        RenderError::Write(error)
+    }
+}
+
+/// Returns the plan a composite specifier such as `%D` expands to.
+pub(crate) fn expansion(pattern: &'static str) -> &'static CompiledFormat {
+    static EXPANSIONS: OnceLock<HashMap<&'static str, CompiledFormat>> = OnceLock::new();
+    let expansions = EXPANSIONS.get_or_init(|| {
+        directive::SPECIFIERS
+            .chars()
+            .filter_map(|specifier| match directive::spec(specifier)?.kind {
+                Kind::Composite(pattern) => Some((pattern, CompiledFormat::parse(pattern))),
+                _ => None,
+            })
+            .collect()
+    });
+    &expansions[pattern]
+}
+
+fn render_directive<T: Formattable + ?Sized, W: fmt::Write>(
+    directive: &Directive,
+    formatter: &Formatter,
+    datetime: &T,
+    strict: bool,
+    out: &mut W,
+) -> Result<(), RenderError> {
+    match directive.modifier {
+        Some(Modifier::AltDigits) => {
+            let digits = formatter
+                .digits()
+                .map_err(|error| FormatError::from_icu(directive.offset, error))?;
+            if let Some(digits) = digits {
+                let plain = Directive {
+                    modifier: None,
+                    ..*directive
+                };
+                let mut rendered = String::new();
+                render_directive(&plain, formatter, datetime, strict, &mut rendered)?;
+                for c in rendered.chars() {
+                    out.write_char(c.to_digit(10).map_or(c, |digit| digits[digit as usize]))?;
+                }
+                return Ok(());
+            }
+        }
+        Some(Modifier::Era) if write_era(out, directive, formatter, datetime)? => return Ok(()),
+        _ => (),
+    }
+
+    let spec = directive::spec(directive.specifier).expect("directive has a known specifier");
+    let width = directive.width.unwrap_or(spec.width);
+    let padding = directive.padding.unwrap_or(spec.padding);
+
+    match spec.kind {
+        Kind::Numeric if directive.epoch_unit.is_some() => {
+            write_epoch(out, directive, datetime, width, padding)?
+        }
+        Kind::Numeric if directive.specifier == 'N' => {
+            let (width, padding) = directive::fraction_layout(directive);
+            write_nanoseconds(out, fraction(datetime), width, padding)?
+        }
+        Kind::Numeric => write_number(out, numeric_value(directive, datetime)?, width, padding)?,
+        Kind::Text => laid_out(out, directive.case, width, padding, |out| {
+            write_text_value(directive, formatter, datetime, out)
+        })?,
+        // Years, %F and offsets are all digits, which case does not change.
+        Kind::Year => write_year(out, directive, datetime, padding)?,
+        // Like GNU, %F hands its width and padding down to the year, so that
+        // %F stays valid ISO 8601 for years beyond 9999.
+        Kind::Composite(_) if directive.specifier == 'F' => {
+            write_iso_date(out, datetime, directive.width, directive.padding)?
+        }
+        Kind::Offset => write_utc_offset(
+            out,
+            utc_offset(directive, datetime)?,
+            directive.colons,
+            directive.width,
+            padding,
+        )?,
+        Kind::Composite(pattern) => laid_out(out, directive.case, width, padding, |mut out| {
+            expansion(pattern)
+                .render_into(formatter, datetime, strict, &mut out)
+                .map_err(|error| error.at(directive.offset))
+        })?,
+    }
+    Ok(())
+}
+
+/// Renders `%EC`, `%Ey` and `%EY` in the era of the locale's calendar.
+/// Returns false for `%Ec`, `%Ex` and `%EX`, which already follow that
+/// calendar, and when the calendar has no era of its own for the date, so
+/// that the directive falls back to `%C`, `%y` or `%Y`.
+fn write_era<W: fmt::Write, T: Formattable + ?Sized>(
+    out: &mut W,
+    directive: &Directive,
+    formatter: &Formatter,
+    datetime: &T,
+) -> Result<bool, RenderError> {
+    let icu_error = |error| FormatError::from_icu(directive.offset, error);
+    if !matches!(directive.specifier, 'C' | 'y' | 'Y') {
+        return Ok(false);
+    }
+    let Some(year) = formatter.era_year(datetime).map_err(icu_error)? else {
+        return Ok(false);
+    };
+    let spec = directive::era_spec(directive.specifier).expect("directive is an era year");
+    let width = directive.width.unwrap_or(spec.width);
+    let padding = directive.padding.unwrap_or(spec.padding);
+    if spec.kind == Kind::Numeric {
+        write_number(out, year.into(), width, padding)?;
+        return Ok(true);
+    }
+    laid_out(out, directive.case, width, padding, |out| {
+        formatter
+            .with_era(directive.specifier, datetime, |value| {
+                write!(out, "{value}")
+            })
+            .map_err(icu_error)?
+            .map_err(RenderError::from)
+    })?;
+    Ok(true)
+}
+
+/// Writes the text `write` produces in `case`, padded to `width`. Only a
+/// case change or padding needs the text in a buffer first.
+fn laid_out<W: fmt::Write>(
+    out: &mut W,
+    case: Case,
+    width: usize,
+    padding: Padding,
+    write: impl FnOnce(&mut dyn fmt::Write) -> Result<(), RenderError>,
+) -> Result<(), RenderError> {
+    if case == Case::Original && (width == 0 || padding == Padding::None) {
+        return write(out);
+    }
+    let mut value = String::new();
+    write(&mut value)?;
+    Ok(write_text(out, &value, case, width, padding)?)
+}
+
+impl RenderError {
+    /// Points a format error at the directive at `offset`.
+    fn at(self, offset: usize) -> Self {
+        match self {
+            RenderError::Format(error) => RenderError::Format(error.at(offset)),
+            error => error,
+        }
+    }
+}
+
+fn numeric_value<T: Formattable + ?Sized>(
+    directive: &Directive,
+    datetime: &T,
+) -> Result<i64, FormatError> {
+    let value = match directive.specifier {
+        'd' | 'e' => datetime.day().into(),
+        'g' => (date(directive, datetime)?.iso_week().year() % 100)
+            .abs()
+            .into(),
+        'H' | 'k' => datetime.hour().into(),
+        'I' | 'l' => hour12(datetime.hour()).into(),
+        'j' => date(directive, datetime)?.ordinal().into(),
+        'm' => datetime.month().into(),
+        'M' => datetime.minute().into(),
+        'q' => ((datetime.month() - 1) / 3 + 1).into(),
+        's' => {
+            let local = formattable::naive(datetime).ok_or(FormatError::InvalidDateTime {
+                offset: directive.offset,
+            })?;
+            local.and_utc().timestamp() - i64::from(utc_offset(directive, datetime)?)
+        }
+        // A leap second is second 60.
+        'S' => (datetime.second() + datetime.nanosecond() / 1_000_000_000).into(),
+        'u' => date(directive, datetime)?
+            .weekday()
+            .number_from_monday()
+            .into(),
+        'U' => {
+            let date = date(directive, datetime)?;
+            week_number(date, date.weekday().num_days_from_sunday())
+        }
+        'V' => date(directive, datetime)?.iso_week().week().into(),
+        'w' => date(directive, datetime)?
+            .weekday()
+            .num_days_from_sunday()
+            .into(),
+        'W' => {
+            let date = date(directive, datetime)?;
+            week_number(date, date.weekday().num_days_from_monday())
+        }
+        'y' => (datetime.year() % 100).abs().into(),
+        specifier => unreachable!("'{specifier}' is not numeric"),
+    };
+    Ok(value)
+}
+
+/// Renders `%{epoch:ms}` and its siblings: the count of the unit since the
+/// epoch, rounded down as `%s` is, then the rest of it after a `.` when a
+/// precision is given. The width covers both.
+fn write_epoch<W: fmt::Write, T: Formattable + ?Sized>(
+    out: &mut W,
+    directive: &Directive,
+    datetime: &T,
+    width: usize,
+    padding: Padding,
+) -> Result<(), RenderError> {
+    let unit = directive
+        .epoch_unit
+        .expect("directive counts time since the epoch");
+    let seconds = numeric_value(directive, datetime)?;
+    // Nanoseconds since the epoch overflow an i64 after the year 2262.
+    let nanoseconds = i128::from(seconds) * 1_000_000_000 + i128::from(fraction(datetime));
+    let count = nanoseconds.div_euclid(unit.nanoseconds().into());
+    let magnitude = count.unsigned_abs();
+    let fraction_width = directive.precision.map_or(0, |precision| precision + 1);
+    write_padded_number(
+        out,
+        (count < 0).then_some('-'),
+        magnitude,
+        magnitude.checked_ilog10().map_or(1, |log| log as usize + 1),
+        width.saturating_sub(fraction_width),
+        padding,
+    )?;
+    if let Some(precision) = directive.precision {
+        let rest = nanoseconds.rem_euclid(unit.nanoseconds().into());
+        let digits = rest / 10_i128.pow((unit.fraction_digits() - precision) as u32);
+        write!(out, ".{digits:0precision$}")?;
+    }
+    Ok(())
+}
+
+/// Renders `%N` the way gnulib does. The fraction is digits after a point
+/// rather than a number, so the first `width` of them, at most 9, are
+/// kept, which makes %3N milliseconds, truncated. Trailing zeros are then
+/// dropped and the rest of the width is padded on the right: with zeros by
+/// default, with spaces for `_` and not at all for `-`.
+fn write_nanoseconds<W: fmt::Write>(
+    out: &mut W,
+    nanoseconds: u32,
+    width: usize,
+    padding: Padding,
+) -> fmt::Result {
+    let mut digits = width.clamp(1, 9);
+    let mut value = nanoseconds / 10_u32.pow((9 - digits) as u32);
+    while digits > 1 && value.is_multiple_of(10) {
+        digits -= 1;
+        value /= 10;
+    }
+    write!(out, "{value:0digits$}")?;
+    match padding {
+        Padding::None => Ok(()),
+        Padding::Space => write_repeated(out, ' ', width.saturating_sub(digits)),
+        Padding::Zero | Padding::Sign => write_repeated(out, '0', width.saturating_sub(digits)),
+    }
+}
+
+/// The calendar date of `datetime`, for the directives that derive a
+/// weekday or week from it.
+fn date<T: Formattable + ?Sized>(
+    directive: &Directive,
+    datetime: &T,
+) -> Result<NaiveDate, FormatError> {
+    NaiveDate::from_ymd_opt(datetime.year(), datetime.month(), datetime.day()).ok_or(
+        FormatError::InvalidDateTime {
+            offset: directive.offset,
+        },
+    )
+}
+
+/// The UTC offset of `datetime` in seconds, which naive values lack.
+fn utc_offset<T: Formattable + ?Sized>(
+    directive: &Directive,
+    datetime: &T,
+) -> Result<i32, FormatError> {
+    datetime.offset().ok_or(FormatError::MissingOffset {
+        specifier: directive.specifier,
+        offset: directive.offset,
+    })
+}
+
+/// Nanoseconds since the start of the second, leaving out the extra
+/// second chrono counts in them during a leap second.
+fn fraction<T: Formattable + ?Sized>(datetime: &T) -> u32 {
+    datetime.nanosecond() % 1_000_000_000
+}
+
+/// The hour on the 12-hour clock, from 1 to 12.
+fn hour12(hour: u32) -> u32 {
+    match hour % 12 {
+        0 => 12,
+        hour => hour,
+    }
+}
+
+/// Week of the year where weeks start on the day `days_from_first` counts
+/// from; days before the first such day are in week 0.
+fn week_number(date: NaiveDate, days_from_first: u32) -> i64 {
+    ((date.ordinal0() + 7 - days_from_first) / 7).into()
+}
+
+/// Lays out a UTC offset the way GNU `date` does for `%z`, `%:z`, `%::z`
+/// and `%:::z`.
+///
+/// The offset is treated as a signed number with colons between its
+/// fields, so padding goes after the sign when it is zeros and before it
+/// when it is spaces. Without colons it is the single number hhmm.
+fn write_utc_offset<W: fmt::Write>(
+    out: &mut W,
+    seconds: i32,
+    colons: usize,
+    width: Option<usize>,
+    padding: Padding,
+) -> fmt::Result {
+    let sign = Some(if seconds < 0 { '-' } else { '+' });
+    let seconds = seconds.unsigned_abs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let colons = match colons {
+        3 if seconds != 0 => 2,
+        3 if minutes != 0 => 1,
+        colons => colons,
+    };
+    // Without colons the hours and minutes are one number, hhmm.
+    let hhmm = hours * 100 + minutes;
+    let hour_digits = digit_count(hours.into());
+    let (length, default_width) = match colons {
+        0 => (digit_count(hhmm.into()), 5),
+        1 => (hour_digits + 3, 6),
+        2 => (hour_digits + 6, 9),
+        _ => (hour_digits, 3),
+    };
+    let width = width.unwrap_or(default_width);
+    match colons {
+        0 => write_padded_number(out, sign, hhmm, length, width, padding),
+        1 => write_padded_number(
+            out,
+            sign,
+            format_args!("{hours}:{minutes:02}"),
+            length,
+            width,
+            padding,
+        ),
+        2 => write_padded_number(
+            out,
+            sign,
+            format_args!("{hours}:{minutes:02}:{seconds:02}"),
+            length,
+            width,
+            padding,
+        ),
+        _ => write_padded_number(out, sign, hours, length, width, padding),
+    }
+}
+
+/// Lays out a year or century the way GNU `date` does.
+///
+/// Negative values keep their `-` sign. With the `+` flag, a `+` is added
+/// when the value has more digits than the field's default `digits`, or
+/// when an explicit width is wider than that default.
+fn write_signed_year<W: fmt::Write>(
+    out: &mut W,
+    negative: bool,
+    magnitude: u32,
+    digits: usize,
+    width: Option<usize>,
+    padding: Padding,
+) -> fmt::Result {
+    let largest = if digits == 2 { 99 } else { 9999 };
+    let sign = if negative {
+        Some('-')
+    } else if padding == Padding::Sign
+        && (magnitude > largest || width.is_some_and(|width| digits < width))
+    {
+        Some('+')
+    } else {
+        None
+    };
+    write_padded_number(
+        out,
+        sign,
+        magnitude,
+        digit_count(magnitude.into()),
+        width.unwrap_or(digits),
+        padding,
+    )
+}
+
+fn write_year<W: fmt::Write, T: Formattable + ?Sized>(
+    out: &mut W,
+    directive: &Directive,
+    datetime: &T,
+    padding: Padding,
+) -> Result<(), RenderError> {
+    let (year, digits) = match directive.specifier {
+        'C' => (datetime.year() / 100, 2),
+        'G' => (date(directive, datetime)?.iso_week().year(), 4),
+        'Y' => (datetime.year(), 4),
+        specifier => unreachable!("'{specifier}' is not a year"),
+    };
+    // The century of years -99 to -1 is 0, yet still printed as negative.
+    let negative = if directive.specifier == 'C' {
+        datetime.year() < 0
+    } else {
+        year < 0
+    };
+    let magnitude = year.unsigned_abs();
+    Ok(write_signed_year(
+        out,
+        negative,
+        magnitude,
+        digits,
+        directive.width,
+        padding,
+    )?)
+}
+
+/// Renders `%F`, which is `%+4Y-%m-%d` unless a width or padding flag is
+/// given; then the width less the 6 characters of `-mm-dd` goes to the year.
+fn write_iso_date<W: fmt::Write, T: Formattable + ?Sized>(
+    out: &mut W,
+    datetime: &T,
+    width: Option<usize>,
+    padding: Option<Padding>,
+) -> fmt::Result {
+    let (year_width, year_padding) = match (width, padding) {
+        (None, None) => (4, Padding::Sign),
+        (width, padding) => (
+            width.unwrap_or(0).saturating_sub(6),
+            padding.unwrap_or(Padding::Zero),
+        ),
+    };
+    let year = datetime.year();
+    write_signed_year(
+        out,
+        year < 0,
+        year.unsigned_abs(),
+        4,
+        Some(year_width),
+        year_padding,
+    )?;
+    write!(out, "-{:02}-{:02}", datetime.month(), datetime.day())
+}
+
+/// Writes a textual value.
+fn write_text_value<T: Formattable + ?Sized>(
+    directive: &Directive,
+    formatter: &Formatter,
+    datetime: &T,
+    out: &mut dyn fmt::Write,
+) -> Result<(), RenderError> {
+    let value = match directive.specifier {
+        '%' => "%",
+        'n' => "\n",
+        't' => "\t",
+        'Z' => {
+            let name = datetime.zone_name().ok_or(FormatError::MissingOffset {
+                specifier: 'Z',
+                offset: directive.offset,
+            })?;
+            return Ok(write!(out, "{name}")?);
+        }
+        'p' if datetime.hour() >= 12 => "PM",
+        'p' => "AM",
+        'P' if datetime.hour() >= 12 => "pm",
+        'P' => "am",
+        'f' => {
+            let fraction = fraction(datetime);
+            // Like chrono, %.f shows 3, 6 or 9 digits as needed, and
+            // nothing for a whole second.
+            let digits = match (directive.precision, fraction) {
+                (Some(precision), _) => precision,
+                (None, 0) => return Ok(()),
+                (None, _) if fraction.is_multiple_of(1_000_000) => 3,
+                (None, _) if fraction.is_multiple_of(1_000) => 6,
+                (None, _) => 9,
+            };
+            let value = fraction / 10_u32.pow(9 - digits as u32);
+            return Ok(write!(out, ".{value:0digits$}")?);
+        }
+        specifier => {
+            return formatter
+                .with_localized(specifier, datetime, |value| write!(out, "{value}"))
+                .map_err(|error| FormatError::from_icu(directive.offset, error))?
+                .map_err(RenderError::from)
+        }
+    };
+    Ok(out.write_str(value)?)
+}
+
+#[cfg(test)]
+mod tests;
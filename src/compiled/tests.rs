@@ -0,0 +1,659 @@
+use chrono::{FixedOffset, TimeZone, Timelike};
+
+use icu::locid::locale;
+
+use crate::{
+    format, try_format, Case, CompiledFormat, Directive, FormatError, Formatter, Item, Locale,
+    Padding, ParseError, Part,
+};
+
+#[test]
+fn compiled_format_items() {
+    let plan = CompiledFormat::parse("at %_5d%^a!");
+    assert_eq!(
+        plan.items(),
+        &[
+            Item::Literal("at ".to_string()),
+            Item::Directive(Directive {
+                specifier: 'd',
+                case: Case::Original,
+                padding: Some(Padding::Space),
+                width: Some(5),
+                precision: None,
+                epoch_unit: None,
+                colons: 0,
+                modifier: None,
+                offset: 3,
+            }),
+            Item::Directive(Directive {
+                specifier: 'a',
+                case: Case::Upper,
+                padding: None,
+                width: None,
+                precision: None,
+                epoch_unit: None,
+                colons: 0,
+                modifier: None,
+                offset: 7,
+            }),
+            Item::Literal("!".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn compiled_format_renders_repeatedly() {
+    let plan = CompiledFormat::parse("%F %T");
+    let offset = FixedOffset::east_opt(3600).unwrap();
+    let first = offset.with_ymd_and_hms(2024, 12, 14, 9, 3, 1).unwrap();
+    let second = offset.with_ymd_and_hms(1999, 1, 2, 23, 59, 59).unwrap();
+    assert_eq!(plan.render(first), "2024-12-14 09:03:01");
+    assert_eq!(plan.render(second), "1999-01-02 23:59:59");
+    assert_eq!(plan.render(first), format("%F %T", first));
+}
+
+#[test]
+fn leading_literal_is_not_a_directive() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("abc%Y", datetime), "abc2024");
+}
+
+#[test]
+fn numeric_defaults_match_gnu() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 7, 0, 0, 5)
+        .unwrap();
+    assert_eq!(format("%S %j %q %U %W", datetime), "05 007 1 01 01");
+}
+
+#[test]
+fn try_format_reports_errors_with_offsets() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    assert_eq!(
+        try_format("%Y %*20", datetime),
+        Err(FormatError::UnknownSpecifier {
+            specifier: '*',
+            offset: 4,
+        })
+    );
+    assert_eq!(
+        try_format("%d/%", datetime),
+        Err(FormatError::DanglingPercent { offset: 3 })
+    );
+    assert_eq!(
+        try_format("%10%", datetime),
+        Err(FormatError::UnknownSpecifier {
+            specifier: '%',
+            offset: 3,
+        })
+    );
+    assert_eq!(
+        try_format("%99999999999999999999d", datetime),
+        Err(FormatError::InvalidWidth { offset: 1 })
+    );
+    assert_eq!(
+        try_format("%2147483648d", datetime),
+        Err(FormatError::InvalidWidth { offset: 1 })
+    );
+    assert_eq!(
+        try_format("%Ez", datetime),
+        Err(FormatError::UnsupportedModifier {
+            modifier: 'E',
+            specifier: 'z',
+            offset: 1,
+        })
+    );
+    assert_eq!(try_format("%%%_3d", datetime), Ok("% 14".to_string()));
+    assert_eq!(format("%999999999999d", datetime), "14");
+}
+
+#[test]
+fn format_stays_lenient() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%Y %*20", datetime), "2024 %*20");
+    assert_eq!(format("%d/%", datetime), "14/%");
+}
+
+#[test]
+fn numeric_utc_offsets() {
+    let kolkata = FixedOffset::east_opt(5 * 3600 + 30 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap();
+    let cases = [
+        ("%z", "+0530"),
+        ("%:z", "+05:30"),
+        ("%::z", "+05:30:00"),
+        ("%:::z", "+05:30"),
+        ("%10z", "+000000530"),
+        ("%_10z", "      +530"),
+        ("%-z", "+530"),
+        ("%10:z", "+000005:30"),
+        ("%_::z", " +5:30:00"),
+    ];
+    for (format_string, expected) in cases {
+        assert_eq!(format(format_string, kolkata), expected, "{format_string}");
+    }
+
+    let st_johns = FixedOffset::west_opt(3 * 3600 + 30 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%z %:::z", st_johns), "-0330 -03:30");
+
+    let utc = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%z %:::z", utc), "+0000 +00");
+    assert_eq!(format("%-z|%_z|%3z|%-:z", utc), "+0|   +0|+00|+0:00");
+
+    // Europe/Berlin kept local mean time, +00:53:28, until 1893.
+    let berlin_lmt = FixedOffset::east_opt(53 * 60 + 28)
+        .unwrap()
+        .with_ymd_and_hms(1890, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(
+        format("%z|%-z|%_z|%::z|%:::z", berlin_lmt),
+        "+0053|+53|  +53|+00:53:28|+00:53:28"
+    );
+    for format_string in ["%F %T %-z", "%F %T %_z"] {
+        let text = format(format_string, berlin_lmt);
+        assert_eq!(
+            crate::parse(&text, format_string),
+            Ok(FixedOffset::east_opt(53 * 60)
+                .unwrap()
+                .with_ymd_and_hms(1890, 1, 1, 0, 0, 0)
+                .unwrap()),
+            "{text:?}"
+        );
+    }
+    let half_hour_west = FixedOffset::west_opt(30 * 60)
+        .unwrap()
+        .with_ymd_and_hms(1900, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%z|%-z|%_6z", half_hour_west), "-0030|-30|   -30");
+
+    let odd = FixedOffset::east_opt(5 * 3600 + 30 * 60 + 15)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%:::z %::z %z", odd), "+05:30:15 +05:30:15 +0530");
+}
+
+#[test]
+fn colons_only_apply_to_z() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(
+        try_format("%:Y", datetime),
+        Err(FormatError::UnsupportedModifier {
+            modifier: ':',
+            specifier: 'Y',
+            offset: 1,
+        })
+    );
+    assert_eq!(
+        try_format("%::::z", datetime),
+        Err(FormatError::UnsupportedModifier {
+            modifier: ':',
+            specifier: 'z',
+            offset: 1,
+        })
+    );
+    assert_eq!(format("%:Y", datetime), "%:Y");
+}
+
+#[test]
+fn years_follow_gnu_sign_rules() {
+    let formats = [
+        "%Y", "%+Y", "%+4Y", "%+6Y", "%_+6Y", "%-+6Y", "%C", "%+C", "%+3C", "%G", "%+G", "%F",
+        "%20F", "%_20F", "%-20F", "%+F", "%+12F", "%8F", "%D", "%y", "%g",
+    ]
+    .join("|");
+    // Expected output of GNU date 9.x for the same instants in UTC.
+    let cases = [
+        (
+            1734134400,
+            "2024|2024|2024|+02024|+02024|+02024|20|20|+20|2024|2024|2024-12-14|\
+             00000000002024-12-14|          2024-12-14|2024-12-14|2024-12-14|+02024-12-14|\
+             2024-12-14|12/14/24|24|24",
+        ),
+        (
+            253402300800,
+            "10000|+10000|+10000|+10000|+10000|+10000|100|+100|+100|9999|9999|+10000-01-01|\
+             00000000010000-01-01|         10000-01-01|10000-01-01|+10000-01-01|+10000-01-01|\
+             10000-01-01|01/01/00|00|99",
+        ),
+        (
+            -62167219200,
+            "0000|0000|0000|+00000|+00000|+00000|00|00|+00|-001|-001|0000-01-01|\
+             00000000000000-01-01|             0-01-01|0-01-01|0-01-01|+00000-01-01|\
+             00-01-01|01/01/00|00|01",
+        ),
+        (
+            -62198755200,
+            "-001|-001|-001|-00001|-00001|-00001|-0|-0|-00|-002|-002|-001-01-01|\
+             -0000000000001-01-01|            -1-01-01|-1-01-01|-1-01-01|-00001-01-01|\
+             -1-01-01|01/01/01|01|02",
+        ),
+        (
+            -377705116800,
+            "-9999|-9999|-9999|-09999|-09999|-09999|-99|-99|-99|-9999|-9999|-9999-01-01|\
+             -0000000009999-01-01|         -9999-01-01|-9999-01-01|-9999-01-01|-09999-01-01|\
+             -9999-01-01|01/01/99|99|99",
+        ),
+        (
+            -59011459200,
+            "0100|0100|0100|+00100|+00100|+00100|01|01|+01|0099|0099|0100-01-01|\
+             00000000000100-01-01|           100-01-01|100-01-01|100-01-01|+00100-01-01|\
+             100-01-01|01/01/00|00|99",
+        ),
+    ];
+    for (timestamp, expected) in cases {
+        let datetime = chrono::DateTime::from_timestamp(timestamp, 0).unwrap();
+        assert_eq!(format(&formats, datetime), expected, "{timestamp}");
+    }
+}
+
+#[test]
+fn fractional_second_precision() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap()
+        + chrono::Duration::nanoseconds(123_456_789);
+    let cases = [
+        ("%N", "123456789"),
+        ("%3N", "123"),
+        ("%6N", "123456"),
+        ("%1N", "1"),
+        ("%12N", "123456789000"),
+        ("%_12N", "123456789   "),
+        ("%-12N", "123456789"),
+        ("%_3N", "123"),
+        ("%T.%3N", "15:04:05.123"),
+        ("%T%.3f", "15:04:05.123"),
+        ("%T%.6f", "15:04:05.123456"),
+        ("%T%.9f", "15:04:05.123456789"),
+        ("%T%.f", "15:04:05.123456789"),
+        ("%s%.2f", "1734188645.12"),
+    ];
+    for (format_string, expected) in cases {
+        assert_eq!(
+            format(format_string, datetime),
+            expected,
+            "{format_string:?}"
+        );
+    }
+    let whole = datetime.with_nanosecond(0).unwrap();
+    assert_eq!(
+        format("%T%.f|%T%.3f|%3N", whole),
+        "15:04:05|15:04:05.000|000"
+    );
+    let millis = datetime.with_nanosecond(20_000_000).unwrap();
+    // Like gnulib, the padding flags drop trailing zeros and pad the
+    // rest of the width on the right; GNU date reads a bare %-N as %9N.
+    assert_eq!(
+        format("%.f|%-3N|%_6N|%_N|%-12N|%-N", millis),
+        ".020|02|02    |02       |02|020000000"
+    );
+    let five_millis = datetime.with_nanosecond(5_000_000).unwrap();
+    assert_eq!(
+        format("%-3N|%_3N|%12N|%_12N|%-12N", five_millis),
+        "005|005|005000000000|005         |005"
+    );
+    assert_eq!(format("%-N|%_N", whole), "000000000|0        ");
+
+    // A quarter of a second before the epoch is second -1 and .750 of it.
+    let before_epoch = chrono::DateTime::from_timestamp(-1, 750_000_000).unwrap();
+    assert_eq!(format("%s.%3N %T%.f", before_epoch), "-1.750 23:59:59.750");
+
+    let leap_second = chrono::NaiveDate::from_ymd_opt(2016, 12, 31)
+        .unwrap()
+        .and_hms_nano_opt(23, 59, 59, 1_250_000_000)
+        .unwrap()
+        .and_utc();
+    assert_eq!(
+        format("%T.%N %S%.3f %r %s", leap_second),
+        "23:59:60.250000000 60.250 11:59:60 PM 1483228799"
+    );
+    for format_string in ["%F %T.%3N %z", "%F %T%.3f %z", "%F %T%.f %z"] {
+        let text = format(format_string, leap_second);
+        assert_eq!(
+            crate::parse(&text, format_string),
+            Ok(leap_second.fixed_offset())
+        );
+    }
+
+    for format_string in [
+        "%s.%3N %z",
+        "%s%.3f %z",
+        "%F %T%.6f %z",
+        "%F %T%.f %z",
+        "%F %T.%12N %z",
+        "%F %T.%_12N %z",
+        "%F %T.%-3N %z",
+        "%F %T.%_N %z",
+        "%F %T.%-12N %z",
+        "%F %T.%30N %z",
+        "%F %T.%_30N %z",
+    ] {
+        let millis = millis.fixed_offset();
+        let text = format(format_string, millis);
+        assert_eq!(
+            crate::parse(&text, format_string),
+            Ok(millis),
+            "{text:?} as {format_string:?}"
+        );
+    }
+    let whole = whole.fixed_offset();
+    assert_eq!(
+        crate::parse("2024-12-14 15:04:05 +0000", "%F %T%.f %z"),
+        Ok(whole)
+    );
+    assert!(crate::parse("2024-12-14 15:04:05 +0000", "%F %T%.3f %z").is_err());
+
+    assert_eq!(
+        CompiledFormat::try_parse("%.0f"),
+        Err(FormatError::InvalidPrecision { offset: 2 })
+    );
+    assert_eq!(
+        CompiledFormat::try_parse("%3.3N"),
+        Err(FormatError::UnsupportedModifier {
+            modifier: '.',
+            specifier: 'N',
+            offset: 2
+        })
+    );
+    assert_eq!(
+        CompiledFormat::try_parse("%f"),
+        Err(FormatError::UnknownSpecifier {
+            specifier: 'f',
+            offset: 1
+        })
+    );
+    assert_eq!(format("%f %.12f %-5.3N", datetime), "%f %.12f %-5.3N");
+
+    let Part::Directive(millis) = &crate::explain("%3N").parts[0] else {
+        panic!("%3N is a directive");
+    };
+    assert_eq!(
+        millis.to_string(),
+        "milliseconds (0-999), truncated, padded on the right to 3 with zeros \
+         (width 3; default padded on the right to 9 with zeros)"
+    );
+    let Part::Directive(spaced) = &crate::explain("%_12N").parts[0] else {
+        panic!("%_12N is a directive");
+    };
+    assert_eq!(
+        spaced.to_string(),
+        "nanoseconds (0-999999999), trailing zeros dropped, padded on the right to 12 with \
+         spaces (flags _, width 12; default padded on the right to 9 with zeros)"
+    );
+    let lints: Vec<_> = crate::lint("%T%.3f %T%.4f")
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        lints,
+        [
+            "%.3f (byte 2): not supported by POSIX, glibc, BSD, musl",
+            "%.4f (byte 9): not supported by POSIX, glibc, BSD, musl, chrono",
+        ]
+    );
+}
+
+#[test]
+fn epoch_units() {
+    let datetime = chrono::DateTime::from_timestamp(1_734_188_645, 123_456_789).unwrap();
+    let cases = [
+        ("%{epoch:s}", "1734188645"),
+        ("%{epoch:ms}", "1734188645123"),
+        ("%{epoch:us}", "1734188645123456"),
+        ("%{epoch:ns}", "1734188645123456789"),
+        ("%{epoch:s.3}", "1734188645.123"),
+        ("%{epoch:ms.3}", "1734188645123.456"),
+        ("%{epoch:us.2}", "1734188645123456.78"),
+        ("[%20{epoch:ms.3}]", "[0001734188645123.456]"),
+        ("[%_20{epoch:ms}] ms", "[       1734188645123] ms"),
+    ];
+    for (format_string, expected) in cases {
+        assert_eq!(
+            format(format_string, datetime),
+            expected,
+            "{format_string:?}"
+        );
+    }
+
+    // 0.75 ms before the epoch is millisecond -1 and .250 of it, as with
+    // %s and %N.
+    let before_epoch = chrono::DateTime::from_timestamp(-1, 999_250_000).unwrap();
+    assert_eq!(
+        format("%{epoch:ms} %{epoch:ms.3} %{epoch:us} %s.%N", before_epoch),
+        "-1 -1.250 -750 -1.999250000"
+    );
+    let naive = before_epoch.naive_utc();
+    assert_eq!(
+        try_format("%{epoch:ms}", naive),
+        Err(FormatError::MissingOffset {
+            specifier: 's',
+            offset: 0
+        })
+    );
+
+    for format_string in [
+        "%{epoch:ns}",
+        "%{epoch:us.3}",
+        "%{epoch:ms.6} %z",
+        "%{epoch:s.9}",
+        "%_25{epoch:ms.6}",
+    ] {
+        for datetime in [datetime, before_epoch] {
+            let text = format(format_string, datetime);
+            assert_eq!(
+                crate::parse(&text, format_string),
+                Ok(datetime.fixed_offset()),
+                "{text:?} as {format_string:?}"
+            );
+        }
+    }
+    // Counts beyond an i64 of nanoseconds parse back as well.
+    let year_4156 = crate::parse("69014572972525020369", "%{epoch:ns}").unwrap();
+    assert_eq!(year_4156.timestamp(), 69_014_572_972);
+    for format_string in [
+        "%{epoch:s.9}",
+        "%{epoch:ms.6}",
+        "%{epoch:us.3}",
+        "%{epoch:ns}",
+    ] {
+        for datetime in [
+            chrono::DateTime::<chrono::Utc>::MIN_UTC,
+            chrono::DateTime::<chrono::Utc>::MAX_UTC,
+        ] {
+            let text = format(format_string, datetime);
+            assert_eq!(
+                crate::parse(&text, format_string),
+                Ok(datetime.fixed_offset()),
+                "{text:?} as {format_string:?}"
+            );
+        }
+    }
+    assert_eq!(
+        crate::parse("1000000000000000000000000000000", "%{epoch:s}"),
+        Err(ParseError::OutOfRange {
+            specifier: 's',
+            offset: 0
+        })
+    );
+    let truncated = crate::parse("1734188645123", "%{epoch:ms}").unwrap();
+    assert_eq!(truncated.timestamp_subsec_nanos(), 123_000_000);
+
+    assert_eq!(
+        CompiledFormat::try_parse("at %{epoch:minutes}"),
+        Err(FormatError::UnknownName {
+            name: "epoch:minutes".to_owned(),
+            offset: 4
+        })
+    );
+    assert_eq!(
+        CompiledFormat::try_parse("%{epoch:us.4}"),
+        Err(FormatError::InvalidPrecision { offset: 11 })
+    );
+    assert_eq!(
+        format("%{epoch:ns.1} %{epoch", datetime),
+        "%{epoch:ns.1} %{epoch"
+    );
+
+    let Part::Directive(millis) = &crate::explain("%{epoch:ms.3}").parts[0] else {
+        panic!("%{{epoch:ms.3}} is a directive");
+    };
+    assert_eq!(
+        millis.to_string(),
+        "milliseconds since 1970-01-01 00:00:00 UTC, and 3 digits of the rest after a '.' \
+         (precision 3)"
+    );
+    assert_eq!(
+        crate::lint("%{epoch:ms}")[0].to_string(),
+        "%{epoch:ms} (byte 0): not supported by POSIX, glibc, BSD, musl, chrono"
+    );
+    assert_eq!(
+        crate::infer_format("1734188645123", &Locale::UND)[0].format,
+        "%{epoch:ms}"
+    );
+}
+
+#[test]
+fn alternative_representations() {
+    let datetime = FixedOffset::east_opt(32400)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 9, 3, 11)
+        .unwrap();
+    let japanese = Formatter::new("ja-JP-u-ca-japanese".parse().unwrap());
+    assert_eq!(
+        japanese.try_format("%EY|%EC|%Ey|%_3Ey|%Y|%C|%y", datetime),
+        Ok("令和6年|令和|6|  6|2024|20|24".to_owned())
+    );
+    assert_eq!(
+        japanese.format("%Ec|%Ex", datetime),
+        japanese.format("%c|%x", datetime)
+    );
+    let heisei = FixedOffset::east_opt(32400)
+        .unwrap()
+        .with_ymd_and_hms(2019, 4, 30, 0, 0, 0)
+        .unwrap();
+    assert_eq!(japanese.format("%EY %m月", heisei), "平成31年 04月");
+    let thai = Formatter::new(locale!("th-TH"));
+    assert_eq!(thai.format("%EY|%Ey", datetime), "พ.ศ. 2567|2567");
+    let english = Formatter::new(locale!("en-US"));
+    assert_eq!(english.format("%EY %EC %Ey", datetime), "2024 20 24");
+    // Without an era of the locale's own, %EY, %EC and %Ey are %Y, %C and
+    // %y, years before 1 included.
+    for year in [0, -1, -99, -100, -2024] {
+        let datetime = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(year, 12, 31, 0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            english.format("%EY|%EC|%Ey|%_6EY|%+EC", datetime),
+            english.format("%Y|%C|%y|%_6Y|%+C", datetime),
+            "{year}"
+        );
+    }
+    let edo = FixedOffset::east_opt(32400)
+        .unwrap()
+        .with_ymd_and_hms(1800, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(japanese.format("%EY|%EC|%Ey", edo), "1800|18|00");
+
+    for (locale, expected) in [
+        ("ar-EG", "١٤/١٢ ٠٩:٠٣:١١ ٩"),
+        ("hi-IN-u-nu-deva", "१४/१२ ०९:०३:११ ९"),
+        ("th-TH-u-nu-thai", "๑๔/๑๒ ๐๙:๐๓:๑๑ ๙"),
+        ("en-US", "14/12 09:03:11 9"),
+    ] {
+        let formatter = Formatter::new(locale.parse().unwrap());
+        assert_eq!(
+            formatter.try_format("%Od/%Om %OH:%OM:%OS %-OI", datetime),
+            Ok(expected.to_owned()),
+            "{locale}"
+        );
+    }
+    let arabic = Formatter::new(locale!("ar-EG"));
+    assert_eq!(arabic.format("%_OI|%OY", datetime), " ٩|2024");
+    assert_eq!(
+        arabic.try_format("%OY", datetime),
+        Err(FormatError::UnsupportedModifier {
+            modifier: 'O',
+            specifier: 'Y',
+            offset: 1
+        })
+    );
+
+    assert_eq!(
+        japanese.parse("令和6年12月14日 +0900", "%EY%m月%d日 %z"),
+        Err(ParseError::Unsupported {
+            specifier: 'Y',
+            offset: 0
+        })
+    );
+    assert_eq!(
+        arabic.parse("١٤", "%Od"),
+        Err(ParseError::Unsupported {
+            specifier: 'd',
+            offset: 0
+        })
+    );
+    for (locale, format_string) in [
+        ("ar-EG", "%x"),
+        ("th-TH-u-nu-thai", "%X"),
+        ("hi-IN-u-nu-deva", "%c"),
+    ] {
+        let formatter = Formatter::new(locale.parse().unwrap());
+        let text = formatter.format(format_string, datetime);
+        assert_eq!(
+            formatter.parse(&text, format_string),
+            Err(ParseError::Unsupported {
+                specifier: format_string.chars().nth(1).unwrap(),
+                offset: 0
+            }),
+            "{text:?} in {locale}"
+        );
+    }
+    let format_string = "%EY-%Om-%Od %OH:%OM:%OS %z";
+    assert_eq!(
+        english.parse(&english.format(format_string, datetime), format_string),
+        Ok(datetime)
+    );
+
+    let Part::Directive(day) = &crate::explain("%Od").parts[0] else {
+        panic!("%Od is a directive");
+    };
+    assert_eq!(
+        day.to_string(),
+        "day of the month (1-31), in the locale's digits, zero-padded to 2"
+    );
+    let lints: Vec<_> = crate::lint("%EY %Od")
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        lints,
+        [
+            "%EY (byte 0): not supported by chrono",
+            "%Od (byte 4): not supported by chrono",
+        ]
+    );
+}
@@ -0,0 +1,110 @@
+use core::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use icu::locid_transform::fallback::LocaleFallbacker;
+use icu_provider::{BufferProvider, DataError};
+use icu_provider_adapters::fallback::LocaleFallbackProvider;
+use icu_provider_blob::BlobDataProvider;
+use icu_provider_fs::FsDataProvider;
+
+pub(crate) type RuntimeProvider = LocaleFallbackProvider<Box<dyn BufferProvider + Send + Sync>>;
+
+/// Where a [`Formatter`](crate::Formatter) loads its ICU locale data from.
+///
+/// The default is the CLDR data compiled into the crate. A runtime source
+/// reads data exported by `icu4x-datagen` instead, either as a directory
+/// tree (`--format dir`, JSON or postcard) or as a postcard blob
+/// (`--format blob`), so that locales can be added or CLDR updated without
+/// a rebuild. Locales missing from runtime data fall back to their parent
+/// locale as with compiled data.
+///
+/// ```
+/// use datefmt::DataSource;
+///
+/// assert!(DataSource::default().is_compiled());
+/// assert!(DataSource::from_dir("/nonexistent/icu4x-data").is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct DataSource {
+    runtime: Option<Arc<RuntimeProvider>>,
+}
+
+impl DataSource {
+    /// The data compiled into the crate.
+    pub fn compiled() -> Self {
+        DataSource::default()
+    }
+
+    /// Loads a data directory written by `icu4x-datagen --format dir`. The
+    /// directory must contain the exporter's `manifest.json`.
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        let provider = FsDataProvider::try_new(path.as_ref())?;
+        Ok(DataSource::runtime(Box::new(provider)))
+    }
+
+    /// Loads a postcard blob written by `icu4x-datagen --format blob`.
+    pub fn from_blob(blob: impl Into<Box<[u8]>>) -> Result<Self, DataError> {
+        let provider = BlobDataProvider::try_new_from_blob(blob.into())?;
+        Ok(DataSource::runtime(Box::new(provider)))
+    }
+
+    /// Loads `path` as a data directory if it is one and as a blob file
+    /// otherwise.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return DataSource::from_dir(path);
+        }
+        let blob =
+            fs::read(path).map_err(|error| DataError::from(error).with_path_context(path))?;
+        DataSource::from_blob(blob)
+    }
+
+    /// Whether this is the data compiled into the crate.
+    pub fn is_compiled(&self) -> bool {
+        self.runtime.is_none()
+    }
+
+    /// The runtime provider, or `None` for compiled data.
+    pub(crate) fn provider(&self) -> Option<&RuntimeProvider> {
+        self.runtime.as_deref()
+    }
+
+    fn runtime(provider: Box<dyn BufferProvider + Send + Sync>) -> Self {
+        // Fallback between locales uses the compiled likely-subtags data, so
+        // runtime data does not need to carry its own.
+        let fallbacker = LocaleFallbacker::new().static_to_owned();
+        DataSource {
+            runtime: Some(Arc::new(LocaleFallbackProvider::new_with_fallbacker(
+                provider, fallbacker,
+            ))),
+        }
+    }
+}
+
+impl PartialEq for DataSource {
+    /// Sources are equal if both are compiled or both share the same loaded
+    /// runtime data.
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.runtime, &other.runtime) {
+            (None, None) => true,
+            (Some(this), Some(other)) => Arc::ptr_eq(this, other),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for DataSource {}
+
+impl fmt::Debug for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.is_compiled() {
+            "compiled"
+        } else {
+            "runtime"
+        };
+        f.debug_tuple("DataSource").field(&kind).finish()
+    }
+}
@@ -0,0 +1,880 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc, Weekday,
+};
+
+use crate::error::DateInputError;
+
+/// Parses a free-form date string the way GNU `date --date` does.
+///
+/// The string is a sequence of items, any of which may be omitted:
+///
+/// - a calendar date: `2024-12-14`, `12/14/24`, `14 Dec 2024`,
+///   `Dec 14, 2024`, `14-Dec-2024` or `20241214`;
+/// - a time of day: `15:04`, `15:04:05.123`, `3pm`, `3:04 pm`, optionally
+///   after the date with a `T` in between;
+/// - a time zone: `UTC`, `Z`, an abbreviation such as `CET` or `EST`, or
+///   a numeric offset such as `+0100` or `-05:00`;
+/// - a day of the week: `friday`, `next friday`, `last sat`;
+/// - relative items: `yesterday`, `tomorrow`, `2 hours ago`, `+1 week`,
+///   `next month`;
+/// - `@SECONDS`, seconds since the Unix epoch.
+///
+/// RFC 2822 dates such as `Sat, 14 Dec 2024 15:04:05 +0100` and the output
+/// of `date` itself are combinations of these. A leading `TZ="Area/City"`
+/// interprets the date in that IANA zone.
+///
+/// Missing fields come from `now`: without a date the day is today's,
+/// and without a time it is midnight when a date or day of the week was
+/// given and the current time otherwise. Local times are resolved in
+/// `now`'s time zone, and the result is returned in that zone too.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+///
+/// let now = FixedOffset::east_opt(0)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 9, 30, 0)
+///     .unwrap();
+/// let parsed = datefmt::parse_date("tomorrow 14:00", &now).unwrap();
+/// assert_eq!(parsed.to_rfc3339(), "2024-12-15T14:00:00+00:00");
+/// let parsed = datefmt::parse_date("2 hours ago", &now).unwrap();
+/// assert_eq!(parsed.to_rfc3339(), "2024-12-14T07:30:00+00:00");
+/// ```
+pub fn parse_date<Tz: TimeZone>(
+    input: &str,
+    now: &DateTime<Tz>,
+) -> Result<DateTime<Tz>, DateInputError> {
+    let mut parser = Parser::new(input)?;
+    while parser.pos < parser.tokens.len() {
+        parser.item()?;
+    }
+    parser.items.resolve(now)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Number,
+    Word,
+    Punct(char),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    offset: usize,
+}
+
+impl Token<'_> {
+    fn unknown(&self) -> DateInputError {
+        DateInputError::UnknownToken {
+            token: self.text.to_owned(),
+            offset: self.offset,
+        }
+    }
+
+    fn out_of_range(&self) -> DateInputError {
+        DateInputError::OutOfRange {
+            token: self.text.to_owned(),
+            offset: self.offset,
+        }
+    }
+
+    fn repeated(&self) -> DateInputError {
+        DateInputError::Repeated {
+            token: self.text.to_owned(),
+            offset: self.offset,
+        }
+    }
+
+    fn is(&self, kind: TokenKind) -> bool {
+        self.kind == kind
+    }
+
+    fn number<T: std::str::FromStr>(&self) -> Result<T, DateInputError> {
+        self.text.parse().map_err(|_| self.out_of_range())
+    }
+
+    /// Reads the token as a number from `min` to `max`.
+    fn number_in(&self, min: u32, max: u32) -> Result<u32, DateInputError> {
+        Some(self.number()?)
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(|| self.out_of_range())
+    }
+
+    /// The part of the token in the byte range `range` of its text, as
+    /// the month of `20241214` is.
+    fn part(&self, range: std::ops::Range<usize>) -> Self {
+        Token {
+            kind: self.kind,
+            text: &self.text[range.clone()],
+            offset: self.offset + range.start,
+        }
+    }
+}
+
+/// Splits `input` from byte `start` into numbers, words and punctuation,
+/// dropping white space and parenthesized comments.
+fn tokenize(input: &str, start: usize) -> Result<Vec<Token<'_>>, DateInputError> {
+    let mut tokens = Vec::new();
+    let mut chars = input[start..]
+        .char_indices()
+        .map(|(index, c)| (start + index, c))
+        .peekable();
+    while let Some((offset, c)) = chars.next() {
+        let kind = if c.is_whitespace() {
+            continue;
+        } else if c == '(' {
+            let mut depth = 1;
+            while depth > 0 {
+                match chars.next() {
+                    Some((_, '(')) => depth += 1,
+                    Some((_, ')')) => depth -= 1,
+                    Some(_) => (),
+                    None => {
+                        return Err(DateInputError::UnexpectedEnd {
+                            offset: input.len(),
+                        })
+                    }
+                }
+            }
+            continue;
+        } else if c.is_ascii_digit() {
+            while chars.next_if(|(_, c)| c.is_ascii_digit()).is_some() {}
+            TokenKind::Number
+        } else if c.is_alphabetic() {
+            while chars.next_if(|(_, c)| c.is_alphabetic()).is_some() {}
+            TokenKind::Word
+        } else if ":-+/,.@".contains(c) {
+            TokenKind::Punct(c)
+        } else {
+            return Err(DateInputError::UnknownToken {
+                token: c.to_string(),
+                offset,
+            });
+        };
+        let end = chars.peek().map_or(input.len(), |&(end, _)| end);
+        tokens.push(Token {
+            kind,
+            text: &input[offset..end],
+            offset,
+        });
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Fixed(FixedOffset),
+    Iana(chrono_tz::Tz),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Date {
+    year: Option<i32>,
+    month: u32,
+    day: u32,
+}
+
+/// Everything a date string said, before it is combined with `now`.
+#[derive(Debug, Default)]
+struct Items {
+    /// The date and the offset of its first token, for error messages.
+    date: Option<(Date, usize)>,
+    /// Hour, minute, second and nanosecond.
+    time: Option<(u32, u32, u32, u32)>,
+    zone: Option<Zone>,
+    /// Ordinal and day, e.g. `(1, Fri)` for `next friday`.
+    weekday: Option<(i64, Weekday)>,
+    /// Seconds and nanoseconds since the epoch, from `@SECONDS`.
+    epoch: Option<(i64, u32)>,
+    years: i64,
+    months: i64,
+    days: i64,
+    seconds: i64,
+    input: String,
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    end: usize,
+    items: Items,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Self, DateInputError> {
+        let mut items = Items {
+            input: input.to_owned(),
+            ..Items::default()
+        };
+        let trimmed = input.trim_start();
+        let mut start = input.len() - trimmed.len();
+        if let Some(rest) = trimmed.strip_prefix("TZ=\"") {
+            let name_start = start + 4;
+            let name_len = rest.find('"').ok_or(DateInputError::UnexpectedEnd {
+                offset: input.len(),
+            })?;
+            let name = &rest[..name_len];
+            let zone = name.parse().map_err(|_| DateInputError::UnknownToken {
+                token: name.to_owned(),
+                offset: name_start,
+            })?;
+            items.zone = Some(Zone::Iana(zone));
+            start = name_start + name_len + 1;
+        }
+        Ok(Parser {
+            tokens: tokenize(input, start)?,
+            pos: 0,
+            end: input.len(),
+            items,
+        })
+    }
+
+    fn peek(&self, ahead: usize) -> Option<Token<'a>> {
+        self.tokens.get(self.pos + ahead).copied()
+    }
+
+    fn peek_is(&self, ahead: usize, kind: TokenKind) -> bool {
+        self.peek(ahead).is_some_and(|token| token.is(kind))
+    }
+
+    fn peek_word(&self, ahead: usize) -> Option<String> {
+        self.peek(ahead)
+            .filter(|token| token.is(TokenKind::Word))
+            .map(|token| token.text.to_lowercase())
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, DateInputError> {
+        let token = self
+            .peek(0)
+            .ok_or(DateInputError::UnexpectedEnd { offset: self.end })?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token<'a>, DateInputError> {
+        let token = self.next()?;
+        if token.is(kind) {
+            Ok(token)
+        } else {
+            Err(token.unknown())
+        }
+    }
+
+    fn skip(&mut self, kind: TokenKind) {
+        if self.peek_is(0, kind) {
+            self.pos += 1;
+        }
+    }
+
+    fn item(&mut self) -> Result<(), DateInputError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::Number => self.number(token),
+            TokenKind::Word => self.word(token),
+            TokenKind::Punct('@') => self.epoch(token),
+            TokenKind::Punct('+' | '-') => self.signed(token),
+            TokenKind::Punct(',') => Ok(()),
+            TokenKind::Punct(_) => Err(token.unknown()),
+        }
+    }
+
+    /// An item starting with a number.
+    fn number(&mut self, token: Token<'a>) -> Result<(), DateInputError> {
+        let next = self.peek(0);
+        let next_word = self.peek_word(0);
+        match next.map(|next| next.kind) {
+            Some(TokenKind::Punct(':')) => return self.time(token),
+            Some(TokenKind::Punct('-')) if self.peek_is(1, TokenKind::Number) => {
+                return self.iso_date(token)
+            }
+            Some(TokenKind::Punct('-'))
+                if self.peek_word(1).is_some_and(|w| month(&w).is_some()) =>
+            {
+                self.pos += 1;
+                return self.day_month(token);
+            }
+            Some(TokenKind::Punct('/')) => return self.slash_date(token),
+            _ => (),
+        }
+        if let Some(word) = next_word {
+            if unit(&word).is_some() {
+                return self.relative(token.number()?, token);
+            }
+            if meridian(&word).is_some() {
+                return self.time(token);
+            }
+            if month(&word).is_some() {
+                return self.day_month(token);
+            }
+        }
+        match token.text.len() {
+            8 => {
+                let date = Date {
+                    year: Some(token.part(0..4).number()?),
+                    month: month_number(token.part(4..6))?,
+                    day: day_number(token.part(6..8))?,
+                };
+                self.set_date(date, token)
+            }
+            // A year after the time, as in the output of `date`.
+            4 if matches!(self.items.date, Some((Date { year: None, .. }, _))) => {
+                if let Some((date, _)) = &mut self.items.date {
+                    date.year = Some(token.number()?);
+                }
+                Ok(())
+            }
+            _ => Err(token.unknown()),
+        }
+    }
+
+    /// `YYYY-MM-DD`, optionally followed by `T` and a time.
+    fn iso_date(&mut self, year: Token<'a>) -> Result<(), DateInputError> {
+        self.expect(TokenKind::Punct('-'))?;
+        let month = self.expect(TokenKind::Number)?;
+        self.expect(TokenKind::Punct('-'))?;
+        let day = self.expect(TokenKind::Number)?;
+        let date = Date {
+            year: Some(year.number()?),
+            month: month_number(month)?,
+            day: day_number(day)?,
+        };
+        self.set_date(date, year)?;
+        if self.peek_word(0).as_deref() == Some("t") && self.peek_is(1, TokenKind::Number) {
+            self.pos += 1;
+            let hour = self.next()?;
+            self.time(hour)?;
+        }
+        Ok(())
+    }
+
+    /// `MM/DD`, `MM/DD/YY`, `MM/DD/YYYY` or `YYYY/MM/DD`.
+    fn slash_date(&mut self, first: Token<'a>) -> Result<(), DateInputError> {
+        self.expect(TokenKind::Punct('/'))?;
+        let second = self.expect(TokenKind::Number)?;
+        let third = if self.peek_is(0, TokenKind::Punct('/')) {
+            self.pos += 1;
+            Some(self.expect(TokenKind::Number)?)
+        } else {
+            None
+        };
+        let date = match third {
+            Some(third) if first.text.len() >= 3 => Date {
+                year: Some(first.number()?),
+                month: month_number(second)?,
+                day: day_number(third)?,
+            },
+            third => Date {
+                year: third.map(year).transpose()?,
+                month: month_number(first)?,
+                day: day_number(second)?,
+            },
+        };
+        self.set_date(date, first)
+    }
+
+    /// `14 Dec`, `14 Dec 2024` or `14-Dec-2024`; the `-` after the day has
+    /// already been consumed.
+    fn day_month(&mut self, day: Token<'a>) -> Result<(), DateInputError> {
+        let name = self.expect(TokenKind::Word)?;
+        let month = month(&name.text.to_lowercase()).ok_or_else(|| name.unknown())?;
+        self.skip(TokenKind::Punct('.'));
+        let year = if self.peek_is(0, TokenKind::Punct('-')) && self.peek_is(1, TokenKind::Number) {
+            self.pos += 1;
+            Some(year(self.next()?)?)
+        } else {
+            self.optional_year()?
+        };
+        let date = Date {
+            year,
+            month,
+            day: day_number(day)?,
+        };
+        self.set_date(date, day)
+    }
+
+    /// `Dec 14` or `Dec 14, 2024`.
+    fn month_day(&mut self, name: Token<'a>, month: u32) -> Result<(), DateInputError> {
+        self.skip(TokenKind::Punct('.'));
+        let day = self.expect(TokenKind::Number)?;
+        if self.peek_is(0, TokenKind::Punct(',')) && self.peek_is(1, TokenKind::Number) {
+            self.pos += 1;
+        }
+        let date = Date {
+            year: self.optional_year()?,
+            month,
+            day: day_number(day)?,
+        };
+        self.set_date(date, name)
+    }
+
+    /// A year after a day and month, unless the number starts a time or a
+    /// relative item.
+    fn optional_year(&mut self) -> Result<Option<i32>, DateInputError> {
+        let starts_other_item = self.peek_is(1, TokenKind::Punct(':'))
+            || self
+                .peek_word(1)
+                .is_some_and(|word| unit(&word).is_some() || meridian(&word).is_some());
+        match self.peek(0) {
+            Some(token) if token.is(TokenKind::Number) && !starts_other_item => {
+                self.pos += 1;
+                year(token).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn set_date(&mut self, date: Date, token: Token<'a>) -> Result<(), DateInputError> {
+        if self.items.date.is_some() || self.items.epoch.is_some() {
+            return Err(token.repeated());
+        }
+        self.items.date = Some((date, token.offset));
+        Ok(())
+    }
+
+    /// `HH[:MM[:SS[.FRACTION]]] [am|pm]`, starting at the hour.
+    fn time(&mut self, hour_token: Token<'a>) -> Result<(), DateInputError> {
+        if self.items.time.is_some() || self.items.epoch.is_some() {
+            return Err(hour_token.repeated());
+        }
+        let mut hour: u32 = hour_token.number()?;
+        let (mut minute, mut second, mut nanosecond) = (0, 0, 0);
+        if self.peek_is(0, TokenKind::Punct(':')) {
+            self.pos += 1;
+            minute = self.field(59)?;
+            if self.peek_is(0, TokenKind::Punct(':')) {
+                self.pos += 1;
+                second = self.field(59)?;
+                if (self.peek_is(0, TokenKind::Punct('.'))
+                    || self.peek_is(0, TokenKind::Punct(',')))
+                    && self.peek_is(1, TokenKind::Number)
+                {
+                    self.pos += 1;
+                    nanosecond = fraction(self.next()?.text);
+                }
+            }
+        }
+        if let Some(pm) = self.peek_word(0).and_then(|word| meridian(&word)) {
+            self.pos += 1;
+            if !(1..=12).contains(&hour) {
+                return Err(hour_token.out_of_range());
+            }
+            hour = hour % 12 + if pm { 12 } else { 0 };
+        }
+        if hour > 23 {
+            return Err(hour_token.out_of_range());
+        }
+        self.items.time = Some((hour, minute, second, nanosecond));
+        Ok(())
+    }
+
+    /// Reads a minute or second no larger than `max`.
+    fn field(&mut self, max: u32) -> Result<u32, DateInputError> {
+        let token = self.expect(TokenKind::Number)?;
+        match token.number()? {
+            value if value <= max => Ok(value),
+            _ => Err(token.out_of_range()),
+        }
+    }
+
+    /// `@SECONDS[.FRACTION]`.
+    fn epoch(&mut self, at: Token<'a>) -> Result<(), DateInputError> {
+        if self.items.epoch.is_some() || self.items.date.is_some() || self.items.time.is_some() {
+            return Err(at.repeated());
+        }
+        let negative = self.peek_is(0, TokenKind::Punct('-'));
+        if negative || self.peek_is(0, TokenKind::Punct('+')) {
+            self.pos += 1;
+        }
+        let seconds: i64 = self.expect(TokenKind::Number)?.number()?;
+        let mut nanoseconds = 0;
+        if (self.peek_is(0, TokenKind::Punct('.')) || self.peek_is(0, TokenKind::Punct(',')))
+            && self.peek_is(1, TokenKind::Number)
+        {
+            self.pos += 1;
+            nanoseconds = fraction(self.next()?.text);
+        }
+        // Negative times count back from the epoch, so -1.5 is 1.5 seconds
+        // before it: -2 seconds plus half a second.
+        self.items.epoch = Some(match (negative, nanoseconds) {
+            (false, _) => (seconds, nanoseconds),
+            (true, 0) => (-seconds, 0),
+            (true, _) => (-seconds - 1, 1_000_000_000 - nanoseconds),
+        });
+        Ok(())
+    }
+
+    /// A signed relative item such as `-1 day`, or a numeric zone such as
+    /// `+0100` after a time.
+    fn signed(&mut self, sign: Token<'a>) -> Result<(), DateInputError> {
+        let number = self.expect(TokenKind::Number)?;
+        let is_relative = self.peek_word(0).is_some_and(|word| unit(&word).is_some());
+        let negative = sign.text == "-";
+        if is_relative {
+            let count: i64 = number.number()?;
+            return self.relative(if negative { -count } else { count }, sign);
+        }
+        if self.items.time.is_none() && self.items.epoch.is_none() {
+            return Err(sign.unknown());
+        }
+        let (hours, minutes): (i32, i32) = match number.text.len() {
+            1 | 2 if self.peek_is(0, TokenKind::Punct(':')) => {
+                self.pos += 1;
+                (number.number()?, self.expect(TokenKind::Number)?.number()?)
+            }
+            1 | 2 => (number.number()?, 0),
+            4 => (
+                number.text[..2].parse().unwrap_or(0),
+                number.text[2..].parse().unwrap_or(0),
+            ),
+            _ => return Err(number.out_of_range()),
+        };
+        if minutes > 59 {
+            return Err(number.out_of_range());
+        }
+        let seconds = (hours * 60 + minutes) * 60;
+        let offset = FixedOffset::east_opt(if negative { -seconds } else { seconds })
+            .ok_or_else(|| number.out_of_range())?;
+        self.set_zone(Zone::Fixed(offset), sign)
+    }
+
+    fn set_zone(&mut self, zone: Zone, token: Token<'a>) -> Result<(), DateInputError> {
+        if self.items.zone.is_some() {
+            return Err(token.repeated());
+        }
+        self.items.zone = Some(zone);
+        Ok(())
+    }
+
+    /// `COUNT UNIT [ago]`, where the count has been read.
+    fn relative(&mut self, count: i64, token: Token<'a>) -> Result<(), DateInputError> {
+        let unit_token = self.expect(TokenKind::Word)?;
+        let (unit, multiplier) =
+            unit(&unit_token.text.to_lowercase()).ok_or_else(|| unit_token.unknown())?;
+        let mut count = count
+            .checked_mul(multiplier)
+            .ok_or_else(|| token.out_of_range())?;
+        if self.peek_word(0).as_deref() == Some("ago") {
+            self.pos += 1;
+            count = -count;
+        }
+        let total = match unit {
+            Unit::Year => &mut self.items.years,
+            Unit::Month => &mut self.items.months,
+            Unit::Day => &mut self.items.days,
+            Unit::Second => &mut self.items.seconds,
+        };
+        *total = total
+            .checked_add(count)
+            .ok_or_else(|| token.out_of_range())?;
+        Ok(())
+    }
+
+    /// An item starting with a word.
+    fn word(&mut self, token: Token<'a>) -> Result<(), DateInputError> {
+        let word = token.text.to_lowercase();
+        if let Some(month) = month(&word) {
+            return self.month_day(token, month);
+        }
+        if let Some(weekday) = weekday(&word) {
+            self.skip(TokenKind::Punct('.'));
+            return self.set_weekday(0, weekday, token);
+        }
+        if unit(&word).is_some() {
+            self.pos -= 1;
+            return self.relative(1, token);
+        }
+        if let Some(ordinal) = ordinal(&word) {
+            let next = self.expect(TokenKind::Word)?;
+            if let Some(weekday) = weekday(&next.text.to_lowercase()) {
+                return self.set_weekday(ordinal, weekday, token);
+            }
+            self.pos -= 1;
+            return self.relative(ordinal, token);
+        }
+        if let Some(offset) = zone_abbreviation(&word) {
+            let offset = FixedOffset::east_opt(offset * 3600).expect("abbreviation is valid");
+            return self.set_zone(Zone::Fixed(offset), token);
+        }
+        match word.as_str() {
+            "today" | "now" => Ok(()),
+            "yesterday" => self.add_days(-1, token),
+            "tomorrow" => self.add_days(1, token),
+            _ => Err(token.unknown()),
+        }
+    }
+
+    fn add_days(&mut self, days: i64, token: Token<'a>) -> Result<(), DateInputError> {
+        self.items.days = self
+            .items
+            .days
+            .checked_add(days)
+            .ok_or_else(|| token.out_of_range())?;
+        Ok(())
+    }
+
+    fn set_weekday(
+        &mut self,
+        ordinal: i64,
+        weekday: Weekday,
+        token: Token<'a>,
+    ) -> Result<(), DateInputError> {
+        if self.items.weekday.is_some() {
+            return Err(token.repeated());
+        }
+        self.items.weekday = Some((ordinal, weekday));
+        self.skip(TokenKind::Punct(','));
+        Ok(())
+    }
+}
+
+impl Items {
+    fn resolve<Tz: TimeZone>(self, now: &DateTime<Tz>) -> Result<DateTime<Tz>, DateInputError> {
+        let invalid = || DateInputError::InvalidDate;
+        let local_now = now.naive_local();
+        let (mut date, time, zone) = match self.epoch {
+            Some((seconds, nanoseconds)) => {
+                let utc = DateTime::from_timestamp(seconds, nanoseconds).ok_or_else(invalid)?;
+                (utc.date_naive(), utc.time(), Some(Zone::Fixed(Utc.fix())))
+            }
+            None => {
+                let date = match self.date {
+                    Some((date, offset)) => {
+                        let year = date.year.unwrap_or(local_now.year());
+                        NaiveDate::from_ymd_opt(year, date.month, date.day).ok_or_else(|| {
+                            DateInputError::OutOfRange {
+                                token: token_at(&self.input, offset),
+                                offset,
+                            }
+                        })?
+                    }
+                    None => local_now.date(),
+                };
+                let time = match self.time {
+                    Some((hour, minute, second, nanosecond)) => {
+                        NaiveTime::from_hms_nano_opt(hour, minute, second, nanosecond)
+                            .expect("time fields are in range")
+                    }
+                    None if self.date.is_some()
+                        || self.weekday.is_some()
+                        || self.input.trim().is_empty() =>
+                    {
+                        NaiveTime::MIN
+                    }
+                    None => local_now.time(),
+                };
+                (date, time, self.zone)
+            }
+        };
+
+        if let (Some((ordinal, weekday)), None) = (self.weekday, self.date) {
+            let today = i64::from(date.weekday().num_days_from_sunday());
+            let target = i64::from(weekday.num_days_from_sunday());
+            let pass = i64::from(ordinal > 0 && today != target);
+            let days = (target - today + 7) % 7 + 7 * (ordinal - pass);
+            date = date
+                .checked_add_signed(Duration::try_days(days).ok_or_else(invalid)?)
+                .ok_or_else(invalid)?;
+        }
+
+        if self.years != 0 || self.months != 0 {
+            let months = i64::from(date.year()) * 12 + i64::from(date.month0());
+            let months = self
+                .years
+                .checked_mul(12)
+                .and_then(|years| months.checked_add(years))
+                .and_then(|months| months.checked_add(self.months))
+                .ok_or_else(invalid)?;
+            let year = i32::try_from(months.div_euclid(12)).map_err(|_| invalid())?;
+            // Like mktime, days past the end of the month carry over into
+            // the next one.
+            date = NaiveDate::from_ymd_opt(year, months.rem_euclid(12) as u32 + 1, 1)
+                .and_then(|first| first.checked_add_signed(Duration::days(date.day0().into())))
+                .ok_or_else(invalid)?;
+        }
+        if self.days != 0 {
+            date = Duration::try_days(self.days)
+                .and_then(|days| date.checked_add_signed(days))
+                .ok_or_else(invalid)?;
+        }
+
+        let local = NaiveDateTime::new(date, time);
+        let resolved = match zone {
+            Some(Zone::Fixed(offset)) => offset
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&now.timezone())),
+            Some(Zone::Iana(zone)) => zone
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&now.timezone())),
+            None => now.timezone().from_local_datetime(&local).earliest(),
+        }
+        .ok_or_else(invalid)?;
+        Duration::try_seconds(self.seconds)
+            .and_then(|seconds| resolved.checked_add_signed(seconds))
+            .ok_or_else(invalid)
+    }
+}
+
+/// The token of `input` that starts at byte `offset`.
+fn token_at(input: &str, offset: usize) -> String {
+    input[offset..]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Reads a month number, from 1 to 12.
+fn month_number(token: Token<'_>) -> Result<u32, DateInputError> {
+    token.number_in(1, 12)
+}
+
+/// Reads a day of the month, from 1 to 31; whether the month has that day
+/// is checked once the date is resolved.
+fn day_number(token: Token<'_>) -> Result<u32, DateInputError> {
+    token.number_in(1, 31)
+}
+
+/// Reads a year, where two digits mean 1969 to 2068 as in POSIX.
+fn year(token: Token<'_>) -> Result<i32, DateInputError> {
+    let year: i32 = token.number()?;
+    Ok(match (token.text.len(), year) {
+        (1 | 2, 69..) => 1900 + year,
+        (1 | 2, _) => 2000 + year,
+        _ => year,
+    })
+}
+
+/// Reads the digits after a decimal point as nanoseconds, ignoring digits
+/// past the ninth.
+fn fraction(digits: &str) -> u32 {
+    let digits = &digits[..digits.len().min(9)];
+    let value: u32 = digits.parse().unwrap_or(0);
+    value * 10u32.pow(9 - digits.len() as u32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Year,
+    Month,
+    Day,
+    Second,
+}
+
+/// A relative unit and how many of its base unit it stands for.
+fn unit(word: &str) -> Option<(Unit, i64)> {
+    let unit = match word {
+        "year" | "years" => (Unit::Year, 1),
+        "month" | "months" => (Unit::Month, 1),
+        "fortnight" | "fortnights" => (Unit::Day, 14),
+        "week" | "weeks" => (Unit::Day, 7),
+        "day" | "days" => (Unit::Day, 1),
+        "hour" | "hours" => (Unit::Second, 3600),
+        "minute" | "minutes" | "min" | "mins" => (Unit::Second, 60),
+        "second" | "seconds" | "sec" | "secs" => (Unit::Second, 1),
+        _ => return None,
+    };
+    Some(unit)
+}
+
+fn ordinal(word: &str) -> Option<i64> {
+    let ordinal = match word {
+        "last" => -1,
+        "this" => 0,
+        "next" | "first" => 1,
+        "third" => 3,
+        "fourth" => 4,
+        "fifth" => 5,
+        "sixth" => 6,
+        "seventh" => 7,
+        "eighth" => 8,
+        "ninth" => 9,
+        "tenth" => 10,
+        "eleventh" => 11,
+        "twelfth" => 12,
+        _ => return None,
+    };
+    Some(ordinal)
+}
+
+/// `Some(true)` for pm and `Some(false)` for am.
+fn meridian(word: &str) -> Option<bool> {
+    match word {
+        "am" => Some(false),
+        "pm" => Some(true),
+        _ => None,
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Month number of an English month name or its abbreviation.
+fn month(word: &str) -> Option<u32> {
+    if word == "sept" {
+        return Some(9);
+    }
+    let index = MONTHS
+        .iter()
+        .position(|name| word == *name || (word.len() == 3 && name.starts_with(word)))?;
+    Some(index as u32 + 1)
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "monday" | "mon" => Weekday::Mon,
+        "tuesday" | "tue" | "tues" => Weekday::Tue,
+        "wednesday" | "wed" | "wednes" => Weekday::Wed,
+        "thursday" | "thu" | "thur" | "thurs" => Weekday::Thu,
+        "friday" | "fri" => Weekday::Fri,
+        "saturday" | "sat" => Weekday::Sat,
+        "sunday" | "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+/// UTC offset in hours of the zone abbreviations GNU `date` understands.
+pub(crate) fn zone_abbreviation(word: &str) -> Option<i32> {
+    let hours = match word {
+        "utc" | "ut" | "gmt" | "z" | "wet" => 0,
+        "west" | "bst" | "cet" | "met" => 1,
+        "cest" | "mest" | "eet" => 2,
+        "eest" | "msk" => 3,
+        "jst" => 9,
+        "ast" => -4,
+        "adt" => -3,
+        "est" => -5,
+        "edt" => -4,
+        "cst" => -6,
+        "cdt" => -5,
+        "mst" => -7,
+        "mdt" => -6,
+        "pst" => -8,
+        "pdt" => -7,
+        "akst" => -9,
+        "akdt" => -8,
+        "hst" => -10,
+        _ => return None,
+    };
+    Some(hours)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,151 @@
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+use crate::{parse_date, DateInputError, Tz};
+
+/// Sunday 2026-10-18 07:12:57 UTC.
+fn now() -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2026, 10, 18, 7, 12, 57)
+        .unwrap()
+}
+
+fn parsed(input: &str) -> String {
+    match parse_date(input, &now()) {
+        Ok(datetime) => datetime.format("%F %T%.f %:z").to_string(),
+        Err(error) => panic!("{input:?}: {error}"),
+    }
+}
+
+#[test]
+fn absolute_dates_and_times() {
+    // Checked against `TZ=UTC date -d INPUT` at the same instant.
+    let cases = [
+        ("", "2026-10-18 00:00:00 +00:00"),
+        ("2024-12-14", "2024-12-14 00:00:00 +00:00"),
+        ("2024-12-14T15:04:05.5Z", "2024-12-14 15:04:05.500 +00:00"),
+        ("20241214", "2024-12-14 00:00:00 +00:00"),
+        ("12/14/24", "2024-12-14 00:00:00 +00:00"),
+        ("12/14", "2026-12-14 00:00:00 +00:00"),
+        ("2024/12/14", "2024-12-14 00:00:00 +00:00"),
+        ("14-Dec-2024", "2024-12-14 00:00:00 +00:00"),
+        ("Dec 14", "2026-12-14 00:00:00 +00:00"),
+        ("Dec 14, 2024 3:04pm", "2024-12-14 15:04:00 +00:00"),
+        ("3pm", "2026-10-18 15:00:00 +00:00"),
+        ("12am", "2026-10-18 00:00:00 +00:00"),
+        ("12:30 pm", "2026-10-18 12:30:00 +00:00"),
+        ("@1700000000", "2023-11-14 22:13:20 +00:00"),
+        ("@-1.5", "1969-12-31 23:59:58.500 +00:00"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parsed(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn zones() {
+    let cases = [
+        (
+            "Sat, 14 Dec 2024 15:04:05 +0100",
+            "2024-12-14 14:04:05 +00:00",
+        ),
+        ("Sat Dec 14 15:04:05 UTC 2024", "2024-12-14 15:04:05 +00:00"),
+        ("2024-12-14 15:04:05 CET", "2024-12-14 14:04:05 +00:00"),
+        (
+            "2024-12-14 15:04:05 (comment) EST",
+            "2024-12-14 20:04:05 +00:00",
+        ),
+        ("15:04 +5", "2026-10-18 10:04:00 +00:00"),
+        ("15:04:05-05:00", "2026-10-18 20:04:05 +00:00"),
+        (
+            "TZ=\"Asia/Tokyo\" 2024-12-14 09:00",
+            "2024-12-14 00:00:00 +00:00",
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parsed(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn relative_items() {
+    let cases = [
+        ("now", "2026-10-18 07:12:57 +00:00"),
+        ("yesterday", "2026-10-17 07:12:57 +00:00"),
+        ("tomorrow 14:00 UTC", "2026-10-19 14:00:00 +00:00"),
+        ("2 hours ago", "2026-10-18 05:12:57 +00:00"),
+        ("hour ago", "2026-10-18 06:12:57 +00:00"),
+        ("1 day ago 3 hours", "2026-10-17 10:12:57 +00:00"),
+        ("-1 day", "2026-10-17 07:12:57 +00:00"),
+        ("1 fortnight ago", "2026-10-04 07:12:57 +00:00"),
+        ("next week", "2026-10-25 07:12:57 +00:00"),
+        ("last month", "2026-09-18 07:12:57 +00:00"),
+        ("next year", "2027-10-18 07:12:57 +00:00"),
+        ("2024-01-31 + 1 month", "2024-03-02 00:00:00 +00:00"),
+        ("2024-03-31 -1 month", "2024-03-02 00:00:00 +00:00"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parsed(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn days_of_the_week() {
+    let cases = [
+        ("sunday", "2026-10-18 00:00:00 +00:00"),
+        ("friday", "2026-10-23 00:00:00 +00:00"),
+        ("this friday", "2026-10-23 00:00:00 +00:00"),
+        ("next sunday", "2026-10-25 00:00:00 +00:00"),
+        ("last sunday", "2026-10-11 00:00:00 +00:00"),
+        ("third friday", "2026-11-06 00:00:00 +00:00"),
+        ("tuesday next week", "2026-10-27 00:00:00 +00:00"),
+        ("Sat. 14 Dec", "2026-12-14 00:00:00 +00:00"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parsed(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn local_times_resolve_in_the_zone_of_now() {
+    let berlin: Tz = "Europe/Berlin".parse().unwrap();
+    let now = berlin.with_ymd_and_hms(2024, 3, 30, 12, 0, 0).unwrap();
+    let parsed = parse_date("tomorrow 12:00", &now).unwrap();
+    assert_eq!(parsed.to_rfc3339(), "2024-03-31T12:00:00+02:00");
+    let parsed = parse_date("2024-07-01 12:00 UTC", &now).unwrap();
+    assert_eq!(parsed.to_rfc3339(), "2024-07-01T14:00:00+02:00");
+    assert_eq!(
+        parse_date("2024-03-31 02:30", &now),
+        Err(DateInputError::InvalidDate)
+    );
+}
+
+#[test]
+fn errors_name_the_token() {
+    let cases = [
+        ("foo", "cannot understand 'foo' at byte 0"),
+        ("2024-12-14 bar", "cannot understand 'bar' at byte 11"),
+        ("2024-02-30", "'2024-02-30' at byte 0 is out of range"),
+        ("2024-13-01", "'13' at byte 5 is out of range"),
+        ("2024-12-32", "'32' at byte 8 is out of range"),
+        ("20241301", "'13' at byte 4 is out of range"),
+        ("13/01/2024", "'13' at byte 0 is out of range"),
+        ("Dec 32, 2024", "'32' at byte 4 is out of range"),
+        ("0-Dec-2024", "'0' at byte 0 is out of range"),
+        ("25:00", "'25' at byte 0 is out of range"),
+        ("23:59:60", "'60' at byte 6 is out of range"),
+        ("12:", "unexpected end of input at byte 3"),
+        (
+            "monday friday",
+            "'friday' at byte 7 repeats an earlier item",
+        ),
+        (
+            "TZ=\"Mars/Olympus\" 12:00",
+            "cannot understand 'Mars/Olympus' at byte 4",
+        ),
+    ];
+    for (input, expected) in cases {
+        let error = parse_date(input, &now()).unwrap_err();
+        assert_eq!(error.to_string(), expected, "{input:?}");
+    }
+}
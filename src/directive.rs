@@ -0,0 +1,582 @@
+use crate::error::FormatError;
+use crate::output::{Case, FormattedOutput, Padding};
+
+/// One piece of a compiled format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    /// Text copied to the output as is.
+    Literal(String),
+    /// A conversion specification such as `%_5d`.
+    Directive(Directive),
+}
+
+/// A parsed conversion specification: flags, width and specifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Directive {
+    /// The conversion character, e.g. `'d'` for `%d`.
+    pub specifier: char,
+    /// Case requested by the `^` or `#` flag.
+    pub case: Case,
+    /// Padding requested by a flag, or `None` for the specifier's default.
+    pub padding: Option<Padding>,
+    /// Explicit field width, or `None` for the specifier's default.
+    pub width: Option<usize>,
+    /// Digits after the decimal point of `%.3f`, or `None` for `%.f`,
+    /// which prints as many as the value needs. Also the digits after the
+    /// decimal point of `%{epoch:ms.3}`.
+    pub precision: Option<usize>,
+    /// The unit of `%{epoch:ms}` and its siblings, whose specifier is `s`;
+    /// `None` for every other directive, `%s` included.
+    pub epoch_unit: Option<EpochUnit>,
+    /// Number of `:` before the specifier, as in `%::z`.
+    pub colons: usize,
+    /// The `E` or `O` modifier, as in `%EY` or `%Od`.
+    pub modifier: Option<Modifier>,
+    /// Byte offset of the directive's `%` in the format string.
+    pub offset: usize,
+}
+
+/// A POSIX modifier asking for an alternative representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    /// `E`: years and eras of the locale's own calendar, such as the
+    /// Japanese one in `ja-JP-u-ca-japanese`, for `%EC`, `%Ey` and `%EY`.
+    /// `%Ec`, `%Ex` and `%EX` are accepted too, and render as `%c`, `%x`
+    /// and `%X` do, which already follow the locale's calendar.
+    Era,
+    /// `O`: digits of the locale's numbering system, such as the
+    /// Arabic-Indic digits of `ar-EG` or the Thai digits of
+    /// `th-TH-u-nu-thai`.
+    AltDigits,
+}
+
+impl Modifier {
+    /// The conversions POSIX allows the modifier on.
+    fn specifiers(self) -> &'static str {
+        match self {
+            Modifier::Era => "cCxXyY",
+            Modifier::AltDigits => "deHIklmMSuUVwWy",
+        }
+    }
+}
+
+/// The unit of time since the Unix epoch that a `%{epoch:...}` directive
+/// counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EpochUnit {
+    /// `%{epoch:s}`, the same count as `%s`.
+    Seconds,
+    /// `%{epoch:ms}`.
+    Milliseconds,
+    /// `%{epoch:us}`.
+    Microseconds,
+    /// `%{epoch:ns}`.
+    Nanoseconds,
+}
+
+impl EpochUnit {
+    /// Nanoseconds in one of the unit.
+    pub(crate) fn nanoseconds(self) -> i64 {
+        match self {
+            EpochUnit::Seconds => 1_000_000_000,
+            EpochUnit::Milliseconds => 1_000_000,
+            EpochUnit::Microseconds => 1_000,
+            EpochUnit::Nanoseconds => 1,
+        }
+    }
+
+    /// The most digits a fraction of the unit can have, down to the
+    /// nanosecond.
+    pub(crate) fn fraction_digits(self) -> usize {
+        match self {
+            EpochUnit::Seconds => 9,
+            EpochUnit::Milliseconds => 6,
+            EpochUnit::Microseconds => 3,
+            EpochUnit::Nanoseconds => 0,
+        }
+    }
+}
+
+/// Reads the name of a braced conversion, such as `epoch:ms` or
+/// `epoch:ms.3`, into its unit and the digits of its precision.
+fn epoch_conversion(name: &str) -> Option<(EpochUnit, Option<&str>)> {
+    let conversion = name.strip_prefix("epoch:")?;
+    let (unit, precision) = match conversion.split_once('.') {
+        Some((unit, precision)) => (unit, Some(precision)),
+        None => (conversion, None),
+    };
+    let unit = match unit {
+        "s" => EpochUnit::Seconds,
+        "ms" => EpochUnit::Milliseconds,
+        "us" => EpochUnit::Microseconds,
+        "ns" => EpochUnit::Nanoseconds,
+        _ => return None,
+    };
+    Some((unit, precision))
+}
+
+/// What a specifier renders to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Numeric,
+    /// A year or century, which can carry a sign.
+    Year,
+    Text,
+    /// A signed UTC offset whose layout depends on the number of colons.
+    Offset,
+    /// Shorthand for another format string, e.g. `%D` for `%m/%d/%y`.
+    Composite(&'static str),
+}
+
+/// Rendering defaults of a specifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Spec {
+    pub kind: Kind,
+    pub width: usize,
+    pub padding: Padding,
+}
+
+impl Spec {
+    const fn numeric(width: usize, padding: Padding) -> Self {
+        Spec {
+            kind: Kind::Numeric,
+            width,
+            padding,
+        }
+    }
+
+    const fn year(digits: usize) -> Self {
+        Spec {
+            kind: Kind::Year,
+            width: digits,
+            padding: Padding::Zero,
+        }
+    }
+
+    const fn text() -> Self {
+        Spec {
+            kind: Kind::Text,
+            width: 0,
+            padding: Padding::None,
+        }
+    }
+
+    const fn offset() -> Self {
+        Spec {
+            kind: Kind::Offset,
+            width: 0,
+            padding: Padding::Zero,
+        }
+    }
+
+    const fn composite(pattern: &'static str) -> Self {
+        Spec {
+            kind: Kind::Composite(pattern),
+            width: 0,
+            padding: Padding::None,
+        }
+    }
+}
+
+/// The widest field a conversion may ask for, as in gnulib's `strftime`.
+const MAX_WIDTH: usize = i32::MAX as usize;
+
+/// Every specifier [`spec`] knows about.
+pub(crate) const SPECIFIERS: &str = "%aAbBcCdDefFgGhHIjklmMnNpPqrRsStTuUVwWxXyYzZ";
+
+/// Looks up the rendering defaults of `specifier`, or `None` if it is not a
+/// known conversion.
+pub(crate) fn spec(specifier: char) -> Option<Spec> {
+    let spec = match specifier {
+        '%' | 'a' | 'A' | 'b' | 'B' | 'c' | 'h' | 'n' | 'p' | 'P' | 't' | 'x' | 'X' | 'Z' => {
+            Spec::text()
+        }
+        'C' => Spec::year(2),
+        'd' => Spec::numeric(2, Padding::Zero),
+        'D' => Spec::composite("%m/%d/%y"),
+        'e' => Spec::numeric(2, Padding::Space),
+        // Only valid as `%.f` or `%.3f`, with its decimal point.
+        'f' => Spec::text(),
+        'F' => Spec::composite("%+4Y-%m-%d"),
+        'g' => Spec::numeric(2, Padding::Zero),
+        'G' => Spec::year(4),
+        'H' => Spec::numeric(2, Padding::Zero),
+        'I' => Spec::numeric(2, Padding::Zero),
+        'j' => Spec::numeric(3, Padding::Zero),
+        'k' => Spec::numeric(2, Padding::Space),
+        'l' => Spec::numeric(2, Padding::Space),
+        'm' => Spec::numeric(2, Padding::Zero),
+        'M' => Spec::numeric(2, Padding::Zero),
+        'N' => Spec::numeric(9, Padding::Zero),
+        'q' => Spec::numeric(0, Padding::Zero),
+        'r' => Spec::composite("%l:%M:%S %p"),
+        'R' => Spec::composite("%H:%M"),
+        's' => Spec::numeric(0, Padding::Zero),
+        'S' => Spec::numeric(2, Padding::Zero),
+        'T' => Spec::composite("%H:%M:%S"),
+        'u' => Spec::numeric(0, Padding::Zero),
+        'U' => Spec::numeric(2, Padding::Zero),
+        'V' => Spec::numeric(2, Padding::Zero),
+        'w' => Spec::numeric(0, Padding::Zero),
+        'W' => Spec::numeric(2, Padding::Zero),
+        'y' => Spec::numeric(2, Padding::Zero),
+        'Y' => Spec::year(4),
+        'z' => Spec::offset(),
+        _ => return None,
+    };
+    Some(spec)
+}
+
+/// The width and padding `%N` is laid out with. GNU `date` reads a bare
+/// `%-N` as `%9N`, all the digits of the clock's resolution, so it keeps
+/// its trailing zeros.
+pub(crate) fn fraction_layout(directive: &Directive) -> (usize, Padding) {
+    match (directive.width, directive.padding) {
+        (None, Some(Padding::None)) => (9, Padding::Zero),
+        (width, padding) => (width.unwrap_or(9), padding.unwrap_or(Padding::Zero)),
+    }
+}
+
+/// Looks up the rendering defaults of `%EC`, `%Ey` or `%EY` when the
+/// locale's calendar has an era of its own for the date. Like glibc, the
+/// year in the era is not padded; the era, alone or with its year, is text.
+pub(crate) fn era_spec(specifier: char) -> Option<Spec> {
+    let spec = match specifier {
+        'y' => Spec::numeric(1, Padding::Zero),
+        'C' | 'Y' => Spec {
+            padding: Padding::Space,
+            ..Spec::text()
+        },
+        _ => return None,
+    };
+    Some(spec)
+}
+
+/// Splits a format string into sections that each start at a `%`, paired
+/// with their byte offset.
+///
+/// Text before the first `%` forms its own section, and `%%` stays together
+/// with whatever follows it.
+pub(crate) fn partition_format_string_into_sections(format_string: &str) -> Vec<(usize, &str)> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+
+    for (index, c) in format_string.char_indices() {
+        let current_section = &format_string[start..index];
+        if c == '%' && current_section == "%" {
+            continue;
+        } else if c == '%' && !current_section.is_empty() {
+            sections.push((start, current_section));
+            start = index;
+        }
+    }
+
+    if start < format_string.len() {
+        sections.push((start, &format_string[start..]));
+    }
+
+    sections
+}
+
+/// A section of a format string, parsed strictly.
+pub(crate) enum Section<'a> {
+    /// A valid section: the text its directive is written as, empty when it
+    /// has none, and its items. A section holds at most one directive,
+    /// followed by literal text.
+    Valid {
+        directive_text: &'a str,
+        items: Vec<Item>,
+    },
+    /// A section that is not a valid conversion, with the literal text
+    /// formatting keeps it as.
+    Invalid { error: FormatError, output: String },
+}
+
+/// Splits `format_string` into sections and parses each one strictly,
+/// falling back to lenient parsing for the output of an invalid section.
+/// Each section is paired with its byte offset and text.
+pub(crate) fn parse_sections(format_string: &str) -> Vec<(usize, &str, Section<'_>)> {
+    partition_format_string_into_sections(format_string)
+        .into_iter()
+        .map(|(offset, text)| {
+            let last = offset + text.len() == format_string.len();
+            let mut items = Vec::new();
+            let section = match parse_section(text, offset, last, true, &mut items) {
+                Ok(()) => Section::Valid {
+                    directive_text: &text[..text.len() - literal_text(&items).len()],
+                    items,
+                },
+                Err(error) => {
+                    items.clear();
+                    parse_section(text, offset, last, false, &mut items)
+                        .expect("lenient parsing does not fail");
+                    Section::Invalid {
+                        error,
+                        output: literal_text(&items),
+                    }
+                }
+            };
+            (offset, text, section)
+        })
+        .collect()
+}
+
+/// Joins the literal text of `items`, leaving out directives.
+fn literal_text(items: &[Item]) -> String {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Literal(text) => Some(text.as_str()),
+            Item::Directive(_) => None,
+        })
+        .collect()
+}
+
+/// Parses the section starting at byte `offset` into its directive and
+/// trailing literal text. `last` tells whether it ends the format string
+/// rather than another section's `%`.
+///
+/// When `strict` is false, a section that does not hold a valid conversion
+/// is kept as literal text, padded to the width it asked for, and
+/// unsupported modifiers are ignored.
+pub(crate) fn parse_section(
+    section_string: &str,
+    offset: usize,
+    last: bool,
+    strict: bool,
+    items: &mut Vec<Item>,
+) -> Result<(), FormatError> {
+    if !section_string.starts_with('%') {
+        items.push(Item::Literal(section_string.to_owned()));
+        return Ok(());
+    }
+
+    let mut section_chars = section_string
+        .char_indices()
+        .map(|(index, c)| (offset + index, c))
+        .peekable();
+    let mut case: Case = Case::Original;
+    let mut padding: Option<Padding> = None;
+    let mut width_string = "".to_string();
+    let mut width_offset = offset;
+
+    section_chars.next();
+    while let Some((_, current_char)) =
+        section_chars.next_if(|&(_, c)| matches!(c, '#' | '-' | '_' | '^' | '+' | '0'))
+    {
+        match current_char {
+            '#' => case = Case::Opposite,
+            '-' => padding = Some(Padding::None),
+            '_' => padding = Some(Padding::Space),
+            '^' => case = Case::Upper,
+            '0' => padding = Some(Padding::Zero),
+            _ => padding = Some(Padding::Sign),
+        }
+    }
+
+    while let Some((index, current_char)) = section_chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+        if width_string.is_empty() {
+            width_offset = index;
+        }
+        width_string.push(current_char);
+    }
+
+    let width = match width_string.parse() {
+        Ok(width) if width <= MAX_WIDTH => Some(width),
+        _ if strict && !width_string.is_empty() => {
+            return Err(FormatError::InvalidWidth {
+                offset: width_offset,
+            })
+        }
+        _ => None,
+    };
+
+    if let Some((brace, _)) = section_chars.next_if(|&(_, c)| c == '{') {
+        let mut name = String::new();
+        let mut closed = false;
+        for (_, c) in section_chars.by_ref() {
+            if c == '}' {
+                closed = true;
+                break;
+            }
+            name.push(c);
+        }
+        let (unit, precision) = match closed.then(|| epoch_conversion(&name)).flatten() {
+            Some(conversion) => conversion,
+            None if strict => {
+                return Err(FormatError::UnknownName {
+                    name,
+                    offset: brace,
+                })
+            }
+            None => {
+                literal_section(section_string, &width_string, padding, items);
+                return Ok(());
+            }
+        };
+        let precision = match precision {
+            None => None,
+            Some(digits) => match digits.parse() {
+                Ok(precision) if (1..=unit.fraction_digits()).contains(&precision) => {
+                    Some(precision)
+                }
+                // The digits end the name, which follows the `{`.
+                _ if strict => {
+                    return Err(FormatError::InvalidPrecision {
+                        offset: brace + name.len() - digits.len() + 1,
+                    })
+                }
+                _ => {
+                    literal_section(section_string, &width_string, padding, items);
+                    return Ok(());
+                }
+            },
+        };
+        items.push(Item::Directive(Directive {
+            specifier: 's',
+            case,
+            padding,
+            width,
+            precision,
+            epoch_unit: Some(unit),
+            colons: 0,
+            modifier: None,
+            offset,
+        }));
+        let rest: String = section_chars.map(|(_, c)| c).collect();
+        if !rest.is_empty() {
+            items.push(Item::Literal(rest));
+        }
+        return Ok(());
+    }
+
+    // chrono's `%.3f`: a fraction of a second with its decimal point.
+    let dot = section_chars
+        .next_if(|&(_, c)| c == '.')
+        .map(|(index, _)| index);
+    let mut precision_string = String::new();
+    while let Some((_, current_char)) = section_chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+        precision_string.push(current_char);
+    }
+    let precision = match precision_string.parse() {
+        Ok(precision @ 1..=9) => Some(precision),
+        _ if precision_string.is_empty() => None,
+        _ if strict => {
+            return Err(FormatError::InvalidPrecision {
+                offset: dot.map_or(offset, |index| index + 1),
+            })
+        }
+        // Not a valid conversion, so it is kept as literal text.
+        _ => {
+            literal_section(section_string, &width_string, padding, items);
+            return Ok(());
+        }
+    };
+
+    let mut colons = 0;
+    let mut colons_offset = offset;
+    while let Some((index, _)) = section_chars.next_if(|&(_, c)| c == ':') {
+        if colons == 0 {
+            colons_offset = index;
+        }
+        colons += 1;
+    }
+
+    let modifier = section_chars.next_if(|&(_, c)| matches!(c, 'E' | 'O'));
+
+    let next = section_chars.next();
+    let known = |specifier| spec(specifier).is_some() && (specifier != 'f' || dot.is_some());
+    let misplaced = match next {
+        _ if colons > 0 && !(colons <= 3 && matches!(next, Some((_, 'z')))) => {
+            Some((':', colons_offset))
+        }
+        Some((_, specifier)) if specifier != 'f' => dot.map(|index| ('.', index)),
+        _ => None,
+    };
+
+    match next {
+        Some((_, specifier)) if known(specifier) && misplaced.is_none() => {
+            let modifier = match modifier {
+                None => None,
+                Some((index, letter)) => {
+                    let modifier = match letter {
+                        'E' => Modifier::Era,
+                        _ => Modifier::AltDigits,
+                    };
+                    if modifier.specifiers().contains(specifier) {
+                        Some(modifier)
+                    } else if strict {
+                        return Err(FormatError::UnsupportedModifier {
+                            modifier: letter,
+                            specifier,
+                            offset: index,
+                        });
+                    } else {
+                        None
+                    }
+                }
+            };
+            items.push(Item::Directive(Directive {
+                specifier,
+                case,
+                padding,
+                width,
+                precision,
+                epoch_unit: None,
+                colons,
+                modifier,
+                offset,
+            }));
+            let rest: String = section_chars.map(|(_, c)| c).collect();
+            if !rest.is_empty() {
+                items.push(Item::Literal(rest));
+            }
+        }
+        Some((_, specifier)) if known(specifier) && strict => {
+            let (modifier, offset) = misplaced.expect("the conversion has a misplaced modifier");
+            return Err(FormatError::UnsupportedModifier {
+                modifier,
+                specifier,
+                offset,
+            });
+        }
+        Some((index, specifier)) if strict => {
+            return Err(FormatError::UnknownSpecifier {
+                specifier,
+                offset: index,
+            })
+        }
+        None if strict && last => return Err(FormatError::DanglingPercent { offset }),
+        // Flags or a width right before a `%`, as in `%10%`.
+        None if strict => {
+            return Err(FormatError::UnknownSpecifier {
+                specifier: '%',
+                offset: offset + section_string.len(),
+            })
+        }
+        _ => literal_section(section_string, &width_string, padding, items),
+    }
+
+    Ok(())
+}
+
+/// Keeps a section that is not a valid conversion as literal text, padded
+/// to the width it asked for.
+fn literal_section(
+    section_string: &str,
+    width_string: &str,
+    padding: Option<Padding>,
+    items: &mut Vec<Item>,
+) {
+    let formatted_output = FormattedOutput::Text {
+        value: section_string.to_owned(),
+        width: width_string
+            .parse()
+            .ok()
+            .filter(|&width| width <= MAX_WIDTH)
+            .unwrap_or(0),
+        padding: padding.unwrap_or(Padding::Space),
+        case: Case::Original,
+    };
+    items.push(Item::Literal(formatted_output.to_string()));
+}
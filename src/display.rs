@@ -0,0 +1,94 @@
+use std::borrow::Cow;
+use std::io;
+
+use core::fmt;
+
+use crate::compiled::CompiledFormat;
+use crate::formattable::Formattable;
+use crate::formatter::Formatter;
+
+/// A datetime paired with a format, rendered when displayed, as returned
+/// by [`display`](crate::display).
+///
+/// Fields are written straight into the destination, so the adapter can
+/// be used inside `write!` and `format_args!` without building a `String`
+/// per datetime. Directives that cannot be rendered produce no output, as
+/// with [`format`](crate::format).
+///
+/// ```
+/// use std::fmt::Write;
+///
+/// use chrono::{FixedOffset, TimeZone};
+/// use datefmt::CompiledFormat;
+///
+/// let plan = CompiledFormat::parse("%d/%b/%Y:%H:%M:%S %z");
+/// let datetime = FixedOffset::east_opt(3600)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 9, 3, 11)
+///     .unwrap();
+/// let mut line = String::new();
+/// write!(line, "[{}] GET /", plan.display(datetime)).unwrap();
+/// assert_eq!(line, "[14/Dec/2024:09:03:11 +0100] GET /");
+/// ```
+pub struct FormatDisplay<'a, T: Formattable> {
+    formatter: &'a Formatter,
+    plan: Cow<'a, CompiledFormat>,
+    datetime: T,
+}
+
+impl<'a, T: Formattable> FormatDisplay<'a, T> {
+    pub(crate) fn new(
+        formatter: &'a Formatter,
+        plan: Cow<'a, CompiledFormat>,
+        datetime: T,
+    ) -> Self {
+        FormatDisplay {
+            formatter,
+            plan,
+            datetime,
+        }
+    }
+}
+
+impl<T: Formattable> fmt::Display for FormatDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter.render_into(f, &self.plan, &self.datetime)
+    }
+}
+
+impl<T: Formattable> fmt::Debug for FormatDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+/// Passes text on to an [`io::Write`], keeping the I/O error that stopped
+/// it, as `write_fmt` does internally.
+pub(crate) struct IoAdapter<'a, W: io::Write + ?Sized> {
+    out: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: io::Write + ?Sized> IoAdapter<'a, W> {
+    pub(crate) fn new(out: &'a mut W) -> Self {
+        IoAdapter { out, error: None }
+    }
+
+    /// The I/O error behind a [`fmt::Error`] this adapter returned.
+    pub(crate) fn into_error(self) -> io::Error {
+        self.error
+            .unwrap_or_else(|| io::Error::other("formatter error"))
+    }
+}
+
+impl<W: io::Write + ?Sized> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,47 @@
+use chrono::{FixedOffset, TimeZone};
+
+use icu::locid::locale;
+
+use crate::{format, CompiledFormat, Formatter};
+
+#[test]
+fn rendering_into_sinks_matches_format() {
+    let datetime = FixedOffset::west_opt(5 * 3600 + 30 * 60)
+        .unwrap()
+        .with_ymd_and_hms(-42, 3, 5, 7, 8, 9)
+        .unwrap();
+    let format_string = "%^a %_10B %-d %+6Y %10D %#p %::z %F %Q %%";
+    let expected = format(format_string, datetime);
+
+    let mut written = String::from(">");
+    crate::format_into(&mut written, format_string, datetime).unwrap();
+    assert_eq!(written, format!(">{expected}"));
+
+    let mut bytes = Vec::new();
+    crate::format_into_writer(&mut bytes, format_string, datetime).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+
+    let plan = CompiledFormat::parse(format_string);
+    assert_eq!(
+        crate::display(format_string, datetime).to_string(),
+        expected
+    );
+    assert_eq!(plan.display(datetime).to_string(), expected);
+    let german = Formatter::new(locale!("de-DE"));
+    assert_eq!(
+        format!("[{}]", german.display(&plan, datetime)),
+        format!("[{}]", german.render(&plan, datetime))
+    );
+
+    struct Full;
+    impl std::io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::StorageFull.into())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let error = crate::format_into_writer(&mut Full, "%F", datetime).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+}
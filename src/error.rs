@@ -0,0 +1,271 @@
+use core::fmt;
+
+use icu::datetime::DateTimeError;
+use icu_provider::{DataError, DataErrorKind};
+
+/// An error from parsing or rendering a format string.
+///
+/// Every variant carries the byte offset in the format string it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// The character after the flags and width is not a known conversion.
+    UnknownSpecifier { specifier: char, offset: usize },
+    /// A `%{...}` conversion whose name is not one of `epoch:s`,
+    /// `epoch:ms`, `epoch:us` and `epoch:ns`, or that lacks its `}`.
+    UnknownName { name: String, offset: usize },
+    /// A `%` with no conversion character after it.
+    DanglingPercent { offset: usize },
+    /// A field width larger than 2147483647, the largest gnulib allows.
+    InvalidWidth { offset: usize },
+    /// Digits after the decimal point of `%.f` that are not from 1 to 9,
+    /// or too many for the unit of `%{epoch:ms.3}`.
+    InvalidPrecision { offset: usize },
+    /// A modifier the conversion does not support.
+    UnsupportedModifier {
+        modifier: char,
+        specifier: char,
+        offset: usize,
+    },
+    /// ICU could not provide data for, or could not format, a directive.
+    DataProvider { offset: usize, message: String },
+    /// The locale data has no entry for a data key a directive needs, e.g.
+    /// `datetime/gregory/datesymbols@1` when runtime data lacks it.
+    MissingData { offset: usize, key: String },
+    /// A conversion that needs a UTC offset, such as `%z`, given a value
+    /// without one, such as a [`NaiveDateTime`](chrono::NaiveDateTime).
+    MissingOffset { specifier: char, offset: usize },
+    /// The fields of the value do not form a valid date and time.
+    InvalidDateTime { offset: usize },
+}
+
+impl FormatError {
+    /// Byte offset in the format string where the problem was found.
+    pub fn offset(&self) -> usize {
+        match self {
+            FormatError::UnknownSpecifier { offset, .. }
+            | FormatError::UnknownName { offset, .. }
+            | FormatError::DanglingPercent { offset }
+            | FormatError::InvalidWidth { offset }
+            | FormatError::InvalidPrecision { offset }
+            | FormatError::UnsupportedModifier { offset, .. }
+            | FormatError::DataProvider { offset, .. }
+            | FormatError::MissingData { offset, .. }
+            | FormatError::MissingOffset { offset, .. }
+            | FormatError::InvalidDateTime { offset } => *offset,
+        }
+    }
+
+    /// Converts an ICU error for the directive at `offset`.
+    pub(crate) fn from_icu(offset: usize, error: DateTimeError) -> Self {
+        match error {
+            DateTimeError::Data(DataError {
+                kind: DataErrorKind::MissingDataKey | DataErrorKind::MissingLocale,
+                key: Some(key),
+                ..
+            }) => FormatError::MissingData {
+                offset,
+                key: key.path().get().to_owned(),
+            },
+            error => FormatError::DataProvider {
+                offset,
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// Moves a rendering error to the directive at `offset`, for errors
+    /// raised while rendering the expansion of a composite such as `%D`.
+    pub(crate) fn at(self, offset: usize) -> Self {
+        match self {
+            FormatError::DataProvider { message, .. } => {
+                FormatError::DataProvider { offset, message }
+            }
+            FormatError::MissingData { key, .. } => FormatError::MissingData { offset, key },
+            FormatError::MissingOffset { specifier, .. } => {
+                FormatError::MissingOffset { specifier, offset }
+            }
+            FormatError::InvalidDateTime { .. } => FormatError::InvalidDateTime { offset },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::UnknownSpecifier { specifier, offset } => {
+                write!(f, "unknown conversion '%{specifier}' at byte {offset}")
+            }
+            FormatError::UnknownName { name, offset } => {
+                write!(f, "unknown conversion '%{{{name}}}' at byte {offset}")
+            }
+            FormatError::DanglingPercent { offset } => {
+                write!(f, "'%' without a conversion at byte {offset}")
+            }
+            FormatError::InvalidWidth { offset } => {
+                write!(f, "field width at byte {offset} is too large")
+            }
+            FormatError::InvalidPrecision { offset } => {
+                write!(f, "precision at byte {offset} is out of range")
+            }
+            FormatError::UnsupportedModifier {
+                modifier,
+                specifier,
+                offset,
+            } => write!(
+                f,
+                "modifier '{modifier}' is not supported by '%{specifier}' at byte {offset}"
+            ),
+            FormatError::DataProvider { offset, message } => {
+                write!(f, "cannot format directive at byte {offset}: {message}")
+            }
+            FormatError::MissingData { offset, key } => {
+                write!(f, "no locale data for '{key}' needed at byte {offset}")
+            }
+            FormatError::MissingOffset { specifier, offset } => write!(
+                f,
+                "'%{specifier}' at byte {offset} needs a UTC offset, which the value lacks"
+            ),
+            FormatError::InvalidDateTime { offset } => {
+                write!(
+                    f,
+                    "the value is not a valid date and time, needed at byte {offset}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// An error from reading a datetime with [`parse`](crate::parse).
+///
+/// Offsets are byte offsets in the input, except where noted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The format string is invalid, or locale data for it is missing.
+    /// Offsets are in the format string.
+    Format(FormatError),
+    /// The input does not continue with the format's literal text.
+    Mismatch { expected: String, offset: usize },
+    /// The input holds no value for a directive where one is expected.
+    InvalidField { specifier: char, offset: usize },
+    /// A value outside its directive's range, such as month 13.
+    OutOfRange { specifier: char, offset: usize },
+    /// A value that disagrees with the other fields, such as the wrong
+    /// day of the week for the date.
+    Conflict { specifier: char, offset: usize },
+    /// Input left over after the whole format was matched.
+    TrailingInput { offset: usize },
+    /// The fields describe a date or time that does not exist, such as
+    /// February 30.
+    InvalidDate,
+    /// The locale's representation for `%c`, `%x` or `%X` could not be
+    /// worked out, for example because it does not use ASCII digits, or a
+    /// `%E` or `%O` conversion asks for era years or digits other than the
+    /// usual ones. The offset is in the format string.
+    Unsupported { specifier: char, offset: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Format(error) => error.fmt(f),
+            ParseError::Mismatch { expected, offset } => {
+                write!(f, "expected '{expected}' at byte {offset}")
+            }
+            ParseError::InvalidField { specifier, offset } => {
+                write!(f, "no value for '%{specifier}' at byte {offset}")
+            }
+            ParseError::OutOfRange { specifier, offset } => {
+                write!(
+                    f,
+                    "value for '%{specifier}' at byte {offset} is out of range"
+                )
+            }
+            ParseError::Conflict { specifier, offset } => write!(
+                f,
+                "value for '%{specifier}' at byte {offset} contradicts the other fields"
+            ),
+            ParseError::TrailingInput { offset } => {
+                write!(f, "unexpected input at byte {offset}")
+            }
+            ParseError::InvalidDate => f.write_str("no such date or time"),
+            ParseError::Unsupported { specifier, offset } => write!(
+                f,
+                "'%{specifier}' at byte {offset} cannot be parsed in this locale"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Format(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<FormatError> for ParseError {
+    fn from(error: FormatError) -> Self {
+        ParseError::Format(error)
+    }
+}
+
+/// An error from [`parse_date`](crate::parse_date).
+///
+/// Variants about a part of the input carry that token and its byte
+/// offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateInputError {
+    /// A token that does not belong to any date, time, zone or relative
+    /// item.
+    UnknownToken { token: String, offset: usize },
+    /// A field outside its range, such as month 13 or hour 25.
+    OutOfRange { token: String, offset: usize },
+    /// A second date, time, zone or day of the week.
+    Repeated { token: String, offset: usize },
+    /// The input ended in the middle of an item, as in `12:`.
+    UnexpectedEnd { offset: usize },
+    /// The items describe a time that does not exist, such as one skipped
+    /// by a daylight saving change, or one outside the supported range.
+    InvalidDate,
+}
+
+impl DateInputError {
+    /// Byte offset in the input where the problem was found, if it is
+    /// about one part of the input.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            DateInputError::UnknownToken { offset, .. }
+            | DateInputError::OutOfRange { offset, .. }
+            | DateInputError::Repeated { offset, .. }
+            | DateInputError::UnexpectedEnd { offset } => Some(*offset),
+            DateInputError::InvalidDate => None,
+        }
+    }
+}
+
+impl fmt::Display for DateInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateInputError::UnknownToken { token, offset } => {
+                write!(f, "cannot understand '{token}' at byte {offset}")
+            }
+            DateInputError::OutOfRange { token, offset } => {
+                write!(f, "'{token}' at byte {offset} is out of range")
+            }
+            DateInputError::Repeated { token, offset } => {
+                write!(f, "'{token}' at byte {offset} repeats an earlier item")
+            }
+            DateInputError::UnexpectedEnd { offset } => {
+                write!(f, "unexpected end of input at byte {offset}")
+            }
+            DateInputError::InvalidDate => f.write_str("no such date or time"),
+        }
+    }
+}
+
+impl std::error::Error for DateInputError {}
@@ -0,0 +1,363 @@
+use core::fmt;
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+use crate::compiled::CompiledFormat;
+use crate::directive::{self, Directive, EpochUnit, Item, Kind, Modifier, Section};
+use crate::error::FormatError;
+use crate::formatter::Formatter;
+use crate::output::{Case, Padding};
+
+/// A format string taken apart directive by directive, as returned by
+/// [`explain`](crate::explain).
+///
+/// The sections come from the parser [`format`](crate::format) uses, so
+/// the explanation always describes what formatting does. Its
+/// [`Display`](fmt::Display) form has one line per section followed by a
+/// sample rendering:
+///
+/// ```
+/// let explanation = datefmt::explain("%G-W%V");
+/// assert_eq!(
+///     explanation.to_string(),
+///     "\
+/// %G   ISO 8601 week-based year, zero-padded to 4
+/// \"-W\" literal text
+/// %V   ISO 8601 week number (1-53), zero-padded to 2
+/// sample for 2024-03-05T07:08:09.123456789+01:00: \"2024-W10\"
+/// "
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The pieces of the format string, in order.
+    pub parts: Vec<Part>,
+    /// The format rendered for [`Explanation::sample_instant`].
+    pub sample: String,
+}
+
+/// One piece of an explained format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    /// Text copied to the output as is.
+    Literal(String),
+    /// A conversion specification.
+    Directive(DirectiveExplanation),
+    /// A section that starts with `%` but is not a valid conversion, and is
+    /// copied to the output as `output`.
+    Invalid {
+        text: String,
+        error: FormatError,
+        output: String,
+    },
+}
+
+/// What one directive renders and how it is laid out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveExplanation {
+    /// The directive as written, e.g. `%_5d`.
+    pub text: String,
+    /// The parsed directive.
+    pub directive: Directive,
+    /// What the directive stands for, e.g. "day of the month (1-31)".
+    pub description: &'static str,
+    /// The width the field is padded to.
+    pub width: usize,
+    /// How the field is padded.
+    pub padding: Padding,
+    /// The width without a width in the directive.
+    pub default_width: usize,
+    /// The padding without a padding flag.
+    pub default_padding: Padding,
+    /// What the directive renders to, which for `%EC`, `%Ey` and `%EY`
+    /// depends on the locale's calendar.
+    kind: Kind,
+}
+
+impl Explanation {
+    /// The instant samples are rendered for: Tuesday 2024-03-05
+    /// 07:08:09.123456789 at UTC+01:00, chosen so that padding shows.
+    pub fn sample_instant() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 5, 7, 8, 9)
+            .unwrap()
+            + chrono::Duration::nanoseconds(123_456_789)
+    }
+
+    /// Explains `format_string`, rendering the sample with `formatter`.
+    pub(crate) fn new(formatter: &Formatter, format_string: &str) -> Self {
+        let sample_instant = Explanation::sample_instant();
+        let era = matches!(formatter.era_year(&sample_instant), Ok(Some(_)));
+        let mut parts = Vec::new();
+        for (_, text, section) in directive::parse_sections(format_string) {
+            match section {
+                Section::Invalid { error, output } => parts.push(Part::Invalid {
+                    text: text.to_owned(),
+                    error,
+                    output,
+                }),
+                Section::Valid {
+                    directive_text,
+                    items,
+                } => {
+                    for item in items {
+                        parts.push(match item {
+                            Item::Literal(text) => Part::Literal(text),
+                            Item::Directive(directive) => Part::Directive(
+                                DirectiveExplanation::new(directive_text, directive, era),
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+        let sample = formatter.render(&CompiledFormat::parse(format_string), sample_instant);
+        Explanation { parts, sample }
+    }
+}
+
+impl DirectiveExplanation {
+    /// Explains `directive`, written as `text`. `era` tells whether the
+    /// locale's calendar has an era of its own for the sample, which `%E`
+    /// years are then rendered in.
+    fn new(text: &str, directive: Directive, era: bool) -> Self {
+        let era_spec = match directive.modifier {
+            Some(Modifier::Era) if era => directive::era_spec(directive.specifier),
+            _ => None,
+        };
+        let spec = era_spec
+            .or_else(|| directive::spec(directive.specifier))
+            .expect("directive has a known specifier");
+        let (width, padding) = match directive.specifier {
+            'N' => directive::fraction_layout(&directive),
+            _ => (
+                directive.width.unwrap_or(spec.width),
+                directive.padding.unwrap_or(spec.padding),
+            ),
+        };
+        DirectiveExplanation {
+            text: text.to_owned(),
+            directive,
+            description: description(&directive),
+            width,
+            padding,
+            default_width: spec.width,
+            default_padding: spec.padding,
+            kind: spec.kind,
+        }
+    }
+
+    /// Describes how the field is laid out in `width` with `padding`.
+    fn layout(&self, width: usize, padding: Padding) -> Option<String> {
+        match self.directive.specifier {
+            'N' => fraction_layout(width, padding),
+            _ => layout(self.kind, width, padding),
+        }
+    }
+
+    /// The flags given, as written.
+    fn flags(&self) -> String {
+        let case = match self.directive.case {
+            Case::Upper => Some('^'),
+            Case::Opposite => Some('#'),
+            Case::Original => None,
+        };
+        let padding = self.directive.padding.map(|padding| match padding {
+            Padding::None => '-',
+            Padding::Space => '_',
+            Padding::Zero => '0',
+            Padding::Sign => '+',
+        });
+        case.into_iter().chain(padding).collect()
+    }
+}
+
+/// Describes how a field of `width` is laid out with `padding`.
+fn layout(kind: Kind, width: usize, padding: Padding) -> Option<String> {
+    let layout = match (kind, padding) {
+        (Kind::Text | Kind::Composite(_), _) if width == 0 => return None,
+        (_, Padding::None) => "not padded".to_owned(),
+        (_, _) if width <= 1 => return None,
+        (Kind::Text | Kind::Composite(_), Padding::Space) => {
+            format!("padded to {width} with spaces")
+        }
+        (Kind::Text | Kind::Composite(_), _) => format!("padded to {width} with zeros"),
+        (_, Padding::Space) => format!("space-padded to {width}"),
+        (Kind::Year, Padding::Sign) => {
+            format!("zero-padded to {width}, with '+' before a longer year")
+        }
+        (_, _) => format!("zero-padded to {width}"),
+    };
+    Some(layout)
+}
+
+/// Describes how `%N` fills a field of `width` with `padding`: trailing
+/// zeros of the fraction are dropped and the rest of the width padded on
+/// the right.
+fn fraction_layout(width: usize, padding: Padding) -> Option<String> {
+    let layout = match padding {
+        Padding::None => "trailing zeros dropped".to_owned(),
+        Padding::Space => {
+            format!("trailing zeros dropped, padded on the right to {width} with spaces")
+        }
+        Padding::Zero | Padding::Sign if width <= 1 => return None,
+        Padding::Zero | Padding::Sign => format!("padded on the right to {width} with zeros"),
+    };
+    Some(layout)
+}
+
+impl fmt::Display for DirectiveExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description)?;
+        if self.directive.specifier == 'z' {
+            f.write_str(match self.directive.colons {
+                0 => " as +hhmm",
+                1 => " as +hh:mm",
+                2 => " as +hh:mm:ss",
+                _ => " as +hh, adding :mm and :ss only when needed",
+            })?;
+        }
+        if let (Some(_), Some(precision)) = (self.directive.epoch_unit, self.directive.precision) {
+            write!(f, ", and {precision} digits of the rest after a '.'")?;
+        }
+        match self.directive.modifier {
+            Some(Modifier::Era) => f.write_str(", counted in the eras of the locale's calendar")?,
+            Some(Modifier::AltDigits) => f.write_str(", in the locale's digits")?,
+            None => (),
+        }
+        match self.directive.case {
+            Case::Upper => f.write_str(", in upper case")?,
+            Case::Opposite => f.write_str(", in lower case")?,
+            Case::Original => (),
+        }
+        // An offset is padded as one number, which is not worth spelling out.
+        let shown = match self.kind {
+            Kind::Offset if self.directive.width.is_none() => None,
+            _ => self.layout(self.width, self.padding),
+        };
+        if let Some(shown) = shown {
+            write!(f, ", {shown}")?;
+        }
+
+        let flags = self.flags();
+        let mut given = Vec::new();
+        if !flags.is_empty() {
+            given.push(format!("flags {flags}"));
+        }
+        if let Some(width) = self.directive.width {
+            given.push(format!("width {width}"));
+        }
+        if let Some(precision) = self.directive.precision {
+            given.push(format!("precision {precision}"));
+        }
+        if self.directive.padding.is_some() || self.directive.width.is_some() {
+            let default = self
+                .layout(self.default_width, self.default_padding)
+                .unwrap_or_else(|| "not padded".to_owned());
+            write!(f, " ({}; default {default})", given.join(", "))?;
+        } else if !given.is_empty() {
+            write!(f, " ({})", given.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = |part: &Part| match part {
+            Part::Literal(text) => format!("{text:?}"),
+            Part::Directive(directive) => directive.text.clone(),
+            Part::Invalid { text, .. } => text.clone(),
+        };
+        let column = self
+            .parts
+            .iter()
+            .map(|part| label(part).chars().count())
+            .max()
+            .unwrap_or(0);
+        for part in &self.parts {
+            let label = label(part);
+            write!(f, "{label:column$} ")?;
+            match part {
+                Part::Literal(_) => writeln!(f, "literal text")?,
+                Part::Directive(directive) => writeln!(f, "{directive}")?,
+                Part::Invalid { error, output, .. } => {
+                    writeln!(f, "not a conversion ({error}), copied as {output:?}")?
+                }
+            }
+        }
+        writeln!(
+            f,
+            "sample for {}: {:?}",
+            Explanation::sample_instant().to_rfc3339(),
+            self.sample
+        )
+    }
+}
+
+/// What `directive` stands for.
+fn description(directive: &Directive) -> &'static str {
+    match directive.specifier {
+        '%' => "a literal '%'",
+        'a' => "abbreviated weekday name",
+        'A' => "full weekday name",
+        'b' | 'h' => "abbreviated month name",
+        'B' => "full month name",
+        'c' => "the locale's date and time",
+        'C' => "century, the year divided by 100",
+        'd' | 'e' => "day of the month (1-31)",
+        'D' => "date, as %m/%d/%y",
+        'F' => "ISO 8601 date, as %+4Y-%m-%d",
+        'g' => "last two digits of the ISO 8601 week-based year",
+        'G' => "ISO 8601 week-based year",
+        'H' | 'k' => "hour on the 24-hour clock (0-23)",
+        'I' | 'l' => "hour on the 12-hour clock (1-12)",
+        'j' => "day of the year (1-366)",
+        'm' => "month (1-12)",
+        'M' => "minute (0-59)",
+        'f' => match directive.precision {
+            None => "fraction of a second after a '.', in 3, 6 or 9 digits, left out when zero",
+            Some(3) => "milliseconds after a '.', truncated",
+            Some(6) => "microseconds after a '.', truncated",
+            Some(9) => "nanoseconds after a '.'",
+            Some(_) => "fraction of a second after a '.', truncated to the precision",
+        },
+        'n' => "a newline",
+        'N' => match directive.width {
+            Some(3) => "milliseconds (0-999), truncated",
+            Some(6) => "microseconds (0-999999), truncated",
+            Some(1..=8) => "fraction of a second, truncated to the width",
+            _ => "nanoseconds (0-999999999)",
+        },
+        'p' => "AM or PM",
+        'P' => "am or pm",
+        'q' => "quarter of the year (1-4)",
+        'r' => "12-hour time, as %l:%M:%S %p",
+        'R' => "24-hour hour and minute, as %H:%M",
+        's' => match directive.epoch_unit {
+            None | Some(EpochUnit::Seconds) => "seconds since 1970-01-01 00:00:00 UTC",
+            Some(EpochUnit::Milliseconds) => "milliseconds since 1970-01-01 00:00:00 UTC",
+            Some(EpochUnit::Microseconds) => "microseconds since 1970-01-01 00:00:00 UTC",
+            Some(EpochUnit::Nanoseconds) => "nanoseconds since 1970-01-01 00:00:00 UTC",
+        },
+        'S' => "second (0-60)",
+        't' => "a tab",
+        'T' => "time, as %H:%M:%S",
+        'u' => "day of the week (1-7), Monday being 1",
+        'U' => "week of the year (0-53), weeks starting on Sunday",
+        'V' => "ISO 8601 week number (1-53)",
+        'w' => "day of the week (0-6), Sunday being 0",
+        'W' => "week of the year (0-53), weeks starting on Monday",
+        'x' => "the locale's date",
+        'X' => "the locale's time",
+        'y' => "last two digits of the year",
+        'Y' => "year",
+        'z' => "UTC offset",
+        'Z' => "time zone abbreviation",
+        specifier => unreachable!("'{specifier}' is not a known conversion"),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,85 @@
+use icu::locid::locale;
+
+use crate::{format, Explanation, Formatter, Padding, Part};
+
+#[test]
+fn explain_directives() {
+    let explanation = crate::explain("%_10-d %^b %G-W%V");
+    let lines: Vec<_> = explanation.to_string().lines().map(str::to_owned).collect();
+    assert_eq!(
+        lines,
+        [
+            "%_10-d  not a conversion (unknown conversion '%-' at byte 4), copied as \"   %_10-d \"",
+            "%^b     abbreviated month name, in upper case (flags ^)",
+            "\" \"     literal text",
+            "%G      ISO 8601 week-based year, zero-padded to 4",
+            "\"-W\"    literal text",
+            "%V      ISO 8601 week number (1-53), zero-padded to 2",
+            "sample for 2024-03-05T07:08:09.123456789+01:00: \"   %_10-d MAR 2024-W10\"",
+        ]
+    );
+    assert_eq!(
+        explanation.sample,
+        format("%_10-d %^b %G-W%V", Explanation::sample_instant())
+    );
+
+    let Part::Directive(day) = &crate::explain("%_5e").parts[0] else {
+        panic!("%_5e is a directive");
+    };
+    assert_eq!((day.width, day.padding), (5, Padding::Space));
+    assert_eq!(
+        (day.default_width, day.default_padding),
+        (2, Padding::Space)
+    );
+    assert_eq!(
+        day.to_string(),
+        "day of the month (1-31), space-padded to 5 (flags _, width 5; default space-padded to 2)"
+    );
+    let Part::Directive(offset) = &crate::explain("%:::z").parts[0] else {
+        panic!("%:::z is a directive");
+    };
+    assert_eq!(
+        offset.to_string(),
+        "UTC offset as +hh, adding :mm and :ss only when needed"
+    );
+    assert_eq!(
+        Formatter::new(locale!("de-DE")).explain("%A").sample,
+        "Dienstag"
+    );
+
+    // %E years are laid out in the era of the locale's calendar, not as %Y.
+    let japanese = Formatter::new("ja-JP-u-ca-japanese".parse().unwrap());
+    let explanation = japanese.explain("%Ey|%EY|%EC|%_3Ey");
+    assert_eq!(explanation.sample, "6|令和6年|令和|  6");
+    let lines: Vec<_> = explanation.to_string().lines().map(str::to_owned).collect();
+    assert_eq!(
+        lines,
+        [
+            "%Ey   last two digits of the year, counted in the eras of the locale's calendar",
+            "\"|\"   literal text",
+            "%EY   year, counted in the eras of the locale's calendar",
+            "\"|\"   literal text",
+            "%EC   century, the year divided by 100, counted in the eras of the locale's calendar",
+            "\"|\"   literal text",
+            "%_3Ey last two digits of the year, counted in the eras of the locale's calendar, space-padded to 3 (flags _, width 3; default not padded)",
+            "sample for 2024-03-05T07:08:09.123456789+01:00: \"6|令和6年|令和|  6\"",
+        ]
+    );
+    let layouts: Vec<_> = explanation
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Directive(directive) => Some((directive.width, directive.padding)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        layouts,
+        [
+            (1, Padding::Zero),
+            (0, Padding::Space),
+            (0, Padding::Space),
+            (3, Padding::Space)
+        ]
+    );
+}
@@ -0,0 +1,433 @@
+use std::time::SystemTime;
+
+use core::fmt;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Offset, TimeZone, Timelike, Utc};
+
+/// A date and time that can be formatted: the fields the conversion
+/// specifiers read.
+///
+/// Implemented for chrono's [`DateTime`](chrono::DateTime),
+/// [`NaiveDateTime`] and [`NaiveDate`], for [`SystemTime`] (in UTC), and
+/// with the `time` and `jiff` features for `time::OffsetDateTime`,
+/// `time::PrimitiveDateTime`, `jiff::Zoned`, `jiff::Timestamp` and
+/// `jiff::civil::DateTime`.
+///
+/// Values without a UTC offset, such as a [`NaiveDateTime`], cannot render
+/// `%z`, `%Z` or `%s`: [`try_format`](crate::try_format) fails with
+/// [`FormatError::MissingOffset`](crate::FormatError::MissingOffset) and
+/// [`format`](crate::format) leaves them out.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use datefmt::FormatError;
+///
+/// let naive = NaiveDate::from_ymd_opt(2024, 3, 5)
+///     .unwrap()
+///     .and_hms_opt(7, 8, 9)
+///     .unwrap();
+/// assert_eq!(datefmt::format("%F %T", naive), "2024-03-05 07:08:09");
+/// assert_eq!(
+///     datefmt::try_format("%T %z", naive),
+///     Err(FormatError::MissingOffset { specifier: 'z', offset: 3 })
+/// );
+/// ```
+pub trait Formattable {
+    /// The year in the proleptic Gregorian calendar, e.g. 2024 or -42.
+    fn year(&self) -> i32;
+    /// The month, from 1 to 12.
+    fn month(&self) -> u32;
+    /// The day of the month, from 1 to 31.
+    fn day(&self) -> u32;
+    /// The hour, from 0 to 23.
+    fn hour(&self) -> u32;
+    /// The minute, from 0 to 59.
+    fn minute(&self) -> u32;
+    /// The second, from 0 to 59.
+    fn second(&self) -> u32;
+    /// Nanoseconds since the start of the second, 1,000,000,000 or more
+    /// during a leap second as in chrono.
+    fn nanosecond(&self) -> u32;
+    /// The UTC offset in seconds east of Greenwich, or `None` for a value
+    /// without one.
+    fn offset(&self) -> Option<i32>;
+    /// What `%Z` prints: the time zone abbreviation, or by default the
+    /// offset as `+hh:mm`.
+    fn zone_name(&self) -> Option<impl fmt::Display + '_> {
+        self.offset().map(OffsetName)
+    }
+    /// Hands `visitor` the value whose fields a render reads, by default
+    /// `self`. A value that converts to get at its fields, such as a
+    /// [`SystemTime`], converts once here rather than in every accessor.
+    #[doc(hidden)]
+    fn with_fields<V: FieldVisitor>(&self, visitor: V) -> V::Output {
+        visitor.visit(self)
+    }
+}
+
+/// What [`Formattable::with_fields`] hands its value to.
+#[doc(hidden)]
+pub trait FieldVisitor {
+    type Output;
+    fn visit<T: Formattable + ?Sized>(self, value: &T) -> Self::Output;
+}
+
+/// An offset shown as `+hh:mm`, or `+hh:mm:ss` when it has seconds, as
+/// chrono shows a [`FixedOffset`](chrono::FixedOffset).
+struct OffsetName(i32);
+
+impl fmt::Display for OffsetName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { '-' } else { '+' };
+        let seconds = self.0.unsigned_abs();
+        write!(f, "{sign}{:02}:{:02}", seconds / 3600, seconds / 60 % 60)?;
+        match seconds % 60 {
+            0 => Ok(()),
+            seconds => write!(f, ":{seconds:02}"),
+        }
+    }
+}
+
+/// The calendar date and wall-clock time of `value`, or `None` if its
+/// fields do not form one.
+pub(crate) fn naive<T: Formattable + ?Sized>(value: &T) -> Option<NaiveDateTime> {
+    NaiveDate::from_ymd_opt(value.year(), value.month(), value.day())?.and_hms_nano_opt(
+        value.hour(),
+        value.minute(),
+        value.second(),
+        value.nanosecond(),
+    )
+}
+
+impl<T: Formattable + ?Sized> Formattable for &T {
+    fn year(&self) -> i32 {
+        (**self).year()
+    }
+    fn month(&self) -> u32 {
+        (**self).month()
+    }
+    fn day(&self) -> u32 {
+        (**self).day()
+    }
+    fn hour(&self) -> u32 {
+        (**self).hour()
+    }
+    fn minute(&self) -> u32 {
+        (**self).minute()
+    }
+    fn second(&self) -> u32 {
+        (**self).second()
+    }
+    fn nanosecond(&self) -> u32 {
+        (**self).nanosecond()
+    }
+    fn offset(&self) -> Option<i32> {
+        (**self).offset()
+    }
+    fn zone_name(&self) -> Option<impl fmt::Display + '_> {
+        (**self).zone_name()
+    }
+    fn with_fields<V: FieldVisitor>(&self, visitor: V) -> V::Output {
+        (**self).with_fields(visitor)
+    }
+}
+
+/// `%Z` prints the offset's [`Display`](fmt::Display) form, which is the
+/// abbreviation for a [`chrono_tz::Tz`] and `+hh:mm` for a
+/// [`FixedOffset`](chrono::FixedOffset).
+impl<Tz: TimeZone> Formattable for chrono::DateTime<Tz>
+where
+    Tz::Offset: fmt::Display,
+{
+    fn year(&self) -> i32 {
+        Datelike::year(self)
+    }
+    fn month(&self) -> u32 {
+        Datelike::month(self)
+    }
+    fn day(&self) -> u32 {
+        Datelike::day(self)
+    }
+    fn hour(&self) -> u32 {
+        Timelike::hour(self)
+    }
+    fn minute(&self) -> u32 {
+        Timelike::minute(self)
+    }
+    fn second(&self) -> u32 {
+        Timelike::second(self)
+    }
+    fn nanosecond(&self) -> u32 {
+        Timelike::nanosecond(self)
+    }
+    fn offset(&self) -> Option<i32> {
+        Some(chrono::DateTime::offset(self).fix().local_minus_utc())
+    }
+    fn zone_name(&self) -> Option<impl fmt::Display + '_> {
+        Some(chrono::DateTime::offset(self))
+    }
+}
+
+impl Formattable for NaiveDateTime {
+    fn year(&self) -> i32 {
+        Datelike::year(self)
+    }
+    fn month(&self) -> u32 {
+        Datelike::month(self)
+    }
+    fn day(&self) -> u32 {
+        Datelike::day(self)
+    }
+    fn hour(&self) -> u32 {
+        Timelike::hour(self)
+    }
+    fn minute(&self) -> u32 {
+        Timelike::minute(self)
+    }
+    fn second(&self) -> u32 {
+        Timelike::second(self)
+    }
+    fn nanosecond(&self) -> u32 {
+        Timelike::nanosecond(self)
+    }
+    fn offset(&self) -> Option<i32> {
+        None
+    }
+}
+
+/// A date renders as its midnight.
+impl Formattable for NaiveDate {
+    fn year(&self) -> i32 {
+        Datelike::year(self)
+    }
+    fn month(&self) -> u32 {
+        Datelike::month(self)
+    }
+    fn day(&self) -> u32 {
+        Datelike::day(self)
+    }
+    fn hour(&self) -> u32 {
+        0
+    }
+    fn minute(&self) -> u32 {
+        0
+    }
+    fn second(&self) -> u32 {
+        0
+    }
+    fn nanosecond(&self) -> u32 {
+        0
+    }
+    fn offset(&self) -> Option<i32> {
+        None
+    }
+}
+
+/// A system time renders in UTC, with `%Z` printing `UTC`.
+impl Formattable for SystemTime {
+    fn year(&self) -> i32 {
+        Datelike::year(&utc(*self))
+    }
+    fn month(&self) -> u32 {
+        Datelike::month(&utc(*self))
+    }
+    fn day(&self) -> u32 {
+        Datelike::day(&utc(*self))
+    }
+    fn hour(&self) -> u32 {
+        Timelike::hour(&utc(*self))
+    }
+    fn minute(&self) -> u32 {
+        Timelike::minute(&utc(*self))
+    }
+    fn second(&self) -> u32 {
+        Timelike::second(&utc(*self))
+    }
+    fn nanosecond(&self) -> u32 {
+        Timelike::nanosecond(&utc(*self))
+    }
+    fn offset(&self) -> Option<i32> {
+        Some(0)
+    }
+    fn zone_name(&self) -> Option<impl fmt::Display + '_> {
+        Some(Utc)
+    }
+    fn with_fields<V: FieldVisitor>(&self, visitor: V) -> V::Output {
+        visitor.visit(&utc(*self))
+    }
+}
+
+fn utc(time: SystemTime) -> chrono::DateTime<Utc> {
+    time.into()
+}
+
+#[cfg(feature = "time")]
+impl Formattable for time::OffsetDateTime {
+    fn year(&self) -> i32 {
+        time::OffsetDateTime::year(*self)
+    }
+    fn month(&self) -> u32 {
+        u8::from(time::OffsetDateTime::month(*self)).into()
+    }
+    fn day(&self) -> u32 {
+        time::OffsetDateTime::day(*self).into()
+    }
+    fn hour(&self) -> u32 {
+        time::OffsetDateTime::hour(*self).into()
+    }
+    fn minute(&self) -> u32 {
+        time::OffsetDateTime::minute(*self).into()
+    }
+    fn second(&self) -> u32 {
+        time::OffsetDateTime::second(*self).into()
+    }
+    fn nanosecond(&self) -> u32 {
+        time::OffsetDateTime::nanosecond(*self)
+    }
+    fn offset(&self) -> Option<i32> {
+        Some(time::OffsetDateTime::offset(*self).whole_seconds())
+    }
+}
+
+#[cfg(feature = "time")]
+impl Formattable for time::PrimitiveDateTime {
+    fn year(&self) -> i32 {
+        time::PrimitiveDateTime::year(*self)
+    }
+    fn month(&self) -> u32 {
+        u8::from(time::PrimitiveDateTime::month(*self)).into()
+    }
+    fn day(&self) -> u32 {
+        time::PrimitiveDateTime::day(*self).into()
+    }
+    fn hour(&self) -> u32 {
+        time::PrimitiveDateTime::hour(*self).into()
+    }
+    fn minute(&self) -> u32 {
+        time::PrimitiveDateTime::minute(*self).into()
+    }
+    fn second(&self) -> u32 {
+        time::PrimitiveDateTime::second(*self).into()
+    }
+    fn nanosecond(&self) -> u32 {
+        time::PrimitiveDateTime::nanosecond(*self)
+    }
+    fn offset(&self) -> Option<i32> {
+        None
+    }
+}
+
+/// `%Z` prints the abbreviation jiff has for the zone at that instant.
+#[cfg(feature = "jiff")]
+impl Formattable for jiff::Zoned {
+    fn year(&self) -> i32 {
+        jiff::Zoned::year(self).into()
+    }
+    fn month(&self) -> u32 {
+        jiff::Zoned::month(self).unsigned_abs().into()
+    }
+    fn day(&self) -> u32 {
+        jiff::Zoned::day(self).unsigned_abs().into()
+    }
+    fn hour(&self) -> u32 {
+        jiff::Zoned::hour(self).unsigned_abs().into()
+    }
+    fn minute(&self) -> u32 {
+        jiff::Zoned::minute(self).unsigned_abs().into()
+    }
+    fn second(&self) -> u32 {
+        jiff::Zoned::second(self).unsigned_abs().into()
+    }
+    fn nanosecond(&self) -> u32 {
+        jiff::Zoned::subsec_nanosecond(self).unsigned_abs()
+    }
+    fn offset(&self) -> Option<i32> {
+        Some(jiff::Zoned::offset(self).seconds())
+    }
+    fn zone_name(&self) -> Option<impl fmt::Display + '_> {
+        Some(JiffAbbreviation(
+            self.time_zone().to_offset_info(self.timestamp()),
+        ))
+    }
+}
+
+#[cfg(feature = "jiff")]
+struct JiffAbbreviation<'t>(jiff::tz::TimeZoneOffsetInfo<'t>);
+
+#[cfg(feature = "jiff")]
+impl fmt::Display for JiffAbbreviation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.abbreviation())
+    }
+}
+
+/// A timestamp renders in UTC, with `%Z` printing `UTC`.
+#[cfg(feature = "jiff")]
+impl Formattable for jiff::Timestamp {
+    fn year(&self) -> i32 {
+        Formattable::year(&jiff_utc(*self))
+    }
+    fn month(&self) -> u32 {
+        Formattable::month(&jiff_utc(*self))
+    }
+    fn day(&self) -> u32 {
+        Formattable::day(&jiff_utc(*self))
+    }
+    fn hour(&self) -> u32 {
+        Formattable::hour(&jiff_utc(*self))
+    }
+    fn minute(&self) -> u32 {
+        Formattable::minute(&jiff_utc(*self))
+    }
+    fn second(&self) -> u32 {
+        Formattable::second(&jiff_utc(*self))
+    }
+    fn nanosecond(&self) -> u32 {
+        Formattable::nanosecond(&jiff_utc(*self))
+    }
+    fn offset(&self) -> Option<i32> {
+        Some(0)
+    }
+    fn zone_name(&self) -> Option<impl fmt::Display + '_> {
+        Some("UTC")
+    }
+    fn with_fields<V: FieldVisitor>(&self, visitor: V) -> V::Output {
+        visitor.visit(&self.to_zoned(jiff::tz::TimeZone::UTC))
+    }
+}
+
+#[cfg(feature = "jiff")]
+fn jiff_utc(timestamp: jiff::Timestamp) -> jiff::civil::DateTime {
+    jiff::tz::Offset::UTC.to_datetime(timestamp)
+}
+
+#[cfg(feature = "jiff")]
+impl Formattable for jiff::civil::DateTime {
+    fn year(&self) -> i32 {
+        jiff::civil::DateTime::year(*self).into()
+    }
+    fn month(&self) -> u32 {
+        jiff::civil::DateTime::month(*self).unsigned_abs().into()
+    }
+    fn day(&self) -> u32 {
+        jiff::civil::DateTime::day(*self).unsigned_abs().into()
+    }
+    fn hour(&self) -> u32 {
+        jiff::civil::DateTime::hour(*self).unsigned_abs().into()
+    }
+    fn minute(&self) -> u32 {
+        jiff::civil::DateTime::minute(*self).unsigned_abs().into()
+    }
+    fn second(&self) -> u32 {
+        jiff::civil::DateTime::second(*self).unsigned_abs().into()
+    }
+    fn nanosecond(&self) -> u32 {
+        jiff::civil::DateTime::subsec_nanosecond(*self).unsigned_abs()
+    }
+    fn offset(&self) -> Option<i32> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests;
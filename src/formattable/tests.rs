@@ -0,0 +1,110 @@
+use chrono::{FixedOffset, TimeZone};
+
+use crate::formattable::FieldVisitor;
+use crate::{format, try_format, CompiledFormat, FormatError, Formattable};
+
+#[test]
+fn formattable_values() {
+    let naive = chrono::NaiveDate::from_ymd_opt(2024, 2, 29)
+        .unwrap()
+        .and_hms_nano_opt(23, 4, 5, 120_000_000)
+        .unwrap();
+    assert_eq!(
+        format("%F %T.%N %p %j %u", naive),
+        "2024-02-29 23:04:05.120000000 PM 060 4"
+    );
+    assert_eq!(format("%F %T", naive.date()), "2024-02-29 00:00:00");
+    assert_eq!(format("[%z|%Z|%s]", naive), "[||]");
+    for (format_string, specifier) in [("%z", 'z'), ("%:z", 'z'), ("%Z", 'Z'), ("%s", 's')] {
+        assert_eq!(
+            try_format(format_string, naive),
+            Err(FormatError::MissingOffset {
+                specifier,
+                offset: 0
+            })
+        );
+    }
+
+    let datetime = FixedOffset::east_opt(3600)
+        .unwrap()
+        .from_local_datetime(&naive)
+        .unwrap();
+    let plan = CompiledFormat::parse("%F %T %z %Z %s");
+    assert_eq!(plan.display(&datetime).to_string(), plan.render(datetime));
+    assert_eq!(format("%Z", datetime), "+01:00");
+
+    let system_time = std::time::SystemTime::from(datetime);
+    assert_eq!(
+        format("%F %T %z %Z %s", system_time),
+        "2024-02-29 22:04:05 +0000 UTC 1709244245"
+    );
+    assert_eq!(
+        format("%c|%+4Y|%::z", system_time),
+        "2/29/24, 10:04:05\u{202f}PM|2024|+00:00:00"
+    );
+
+    // A value that converts to get at its fields converts once per render,
+    // however many directives read them.
+    struct Converting<'a>(&'a std::cell::Cell<u32>, chrono::DateTime<FixedOffset>);
+    impl Formattable for Converting<'_> {
+        fn year(&self) -> i32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn month(&self) -> u32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn day(&self) -> u32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn hour(&self) -> u32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn minute(&self) -> u32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn second(&self) -> u32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn nanosecond(&self) -> u32 {
+            unreachable!("fields are read from the converted value")
+        }
+        fn offset(&self) -> Option<i32> {
+            unreachable!("fields are read from the converted value")
+        }
+        fn with_fields<V: FieldVisitor>(&self, visitor: V) -> V::Output {
+            self.0.set(self.0.get() + 1);
+            visitor.visit(&self.1)
+        }
+    }
+    let conversions = std::cell::Cell::new(0);
+    assert_eq!(
+        format("%c %s %Z %D", Converting(&conversions, datetime)),
+        "2/29/24, 11:04:05\u{202f}PM 1709244245 +01:00 02/29/24"
+    );
+    assert_eq!(conversions.get(), 1);
+
+    #[cfg(feature = "time")]
+    {
+        let offset = time::UtcOffset::from_hms(1, 0, 0).unwrap();
+        let value = time::OffsetDateTime::from_unix_timestamp(1709244245)
+            .unwrap()
+            .to_offset(offset);
+        assert_eq!(
+            format("%F %T %z %s", value),
+            "2024-02-29 23:04:05 +0100 1709244245"
+        );
+    }
+    #[cfg(feature = "jiff")]
+    {
+        let value = jiff::Timestamp::from_second(1709244245).unwrap();
+        assert_eq!(
+            format("%F %T %z %Z", value),
+            "2024-02-29 22:04:05 +0000 UTC"
+        );
+        let zoned = value.to_zoned(jiff::tz::TimeZone::fixed(jiff::tz::offset(-5)));
+        assert_eq!(
+            format("%F %T %:z %s", zoned),
+            "2024-02-29 17:04:05 -05:00 1709244245"
+        );
+    }
+}
@@ -0,0 +1,524 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard};
+
+use core::fmt;
+
+use chrono::FixedOffset;
+use icu::calendar::{AnyCalendar, AnyCalendarKind, Date, DateTime, Ref};
+use icu::datetime::options::{components, length};
+use icu::datetime::provider::calendar::DateSkeletonPatternsV1Marker;
+use icu::datetime::provider::Baked;
+use icu::datetime::DateTimeFormatterOptions;
+use icu::datetime::{DateTimeError, DateTimeFormatter};
+use icu::decimal::FixedDecimalFormatter;
+use icu::locid::extensions::unicode::{key, value};
+use icu::locid::{locale, Locale};
+use icu_provider::{
+    AsDeserializingBufferProvider, BufferProvider, DataError, DataErrorKind, DataLocale,
+    DataProvider, DataRequest, DataRequestMetadata, KeyedDataMarker,
+};
+
+use crate::compiled::{CompiledFormat, RenderError};
+use crate::data::DataSource;
+use crate::display::{FormatDisplay, IoAdapter};
+use crate::error::{FormatError, ParseError};
+use crate::explain::Explanation;
+use crate::formattable::Formattable;
+use crate::infer::{self, Candidate};
+use crate::parser;
+
+/// Formats datetimes with the month and weekday names and the preferred
+/// date and time representations (`%c`, `%x`, `%X`) of one locale.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use datefmt::Formatter;
+/// use icu::locid::locale;
+///
+/// let formatter = Formatter::new(locale!("de-DE"));
+/// let datetime = FixedOffset::east_opt(3600)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+///     .unwrap();
+/// assert_eq!(formatter.format("%A, %d. %B %Y", datetime), "Samstag, 14. Dezember 2024");
+/// ```
+///
+/// Locales without data of their own fall back to their parent locale and,
+/// failing that, to English. Names come from the compiled CLDR data unless
+/// another [`DataSource`] is given with [`with_data`](Self::with_data).
+///
+/// ICU formatters are built on first use and cached, so a `Formatter`
+/// should be kept and reused rather than created per call. It can be shared
+/// between threads, and clones share the cache.
+#[derive(Clone)]
+pub struct Formatter {
+    locale: Locale,
+    data: DataSource,
+    cache: Arc<RwLock<HashMap<CacheKey, Arc<DateTimeFormatter>>>>,
+    /// The locale ICU data is loaded for, for `locale` or English.
+    data_locale: Arc<OnceLock<Locale>>,
+    /// The locale's calendar when it has eras of its own, for `%E`.
+    calendar: Arc<OnceLock<Result<Option<AnyCalendar>, DateTimeError>>>,
+    /// The locale's digits when they are not the ASCII ones, for `%O`.
+    digits: Arc<OnceLock<Result<Option<[char; 10]>, DateTimeError>>>,
+}
+
+/// The locale asked for and the options built from a specifier.
+type CacheKey = (Locale, DateTimeFormatterOptions);
+
+impl fmt::Debug for Formatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Formatter")
+            .field("locale", &self.locale)
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Formatter {
+    /// Formatters are equal if they format with the same locale and data.
+    fn eq(&self, other: &Self) -> bool {
+        self.locale == other.locale && self.data == other.data
+    }
+}
+
+impl Eq for Formatter {}
+
+impl Default for Formatter {
+    /// A formatter for `en-US`.
+    fn default() -> Self {
+        Formatter::new(locale!("en-US"))
+    }
+}
+
+impl Formatter {
+    /// Creates a formatter for `locale`.
+    pub fn new(locale: Locale) -> Self {
+        Formatter {
+            locale,
+            data: DataSource::default(),
+            cache: Arc::default(),
+            data_locale: Arc::default(),
+            calendar: Arc::default(),
+            digits: Arc::default(),
+        }
+    }
+
+    /// The `en-US` formatter shared by the free functions, so that its cache
+    /// lives for the whole program.
+    pub(crate) fn shared_default() -> &'static Formatter {
+        static DEFAULT: OnceLock<Formatter> = OnceLock::new();
+        DEFAULT.get_or_init(Formatter::default)
+    }
+
+    /// Loads locale data from `data` instead of the compiled data.
+    pub fn with_data(mut self, data: DataSource) -> Self {
+        self.data = data;
+        self.cache = Arc::default();
+        self.data_locale = Arc::default();
+        self.calendar = Arc::default();
+        self.digits = Arc::default();
+        self
+    }
+
+    /// The locale names are taken from.
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// Where locale data is loaded from.
+    pub fn data(&self) -> &DataSource {
+        &self.data
+    }
+
+    /// Formats `datetime` according to `format_string`, copying invalid
+    /// conversions to the output as [`format`](crate::format) does.
+    pub fn format<T: Formattable>(&self, format_string: &str, datetime: T) -> String {
+        self.render(&CompiledFormat::parse(format_string), datetime)
+    }
+
+    /// Formats `datetime` according to `format_string`, rejecting invalid
+    /// conversions as [`try_format`](crate::try_format) does.
+    pub fn try_format<T: Formattable>(
+        &self,
+        format_string: &str,
+        datetime: T,
+    ) -> Result<String, FormatError> {
+        self.try_render(&CompiledFormat::try_parse(format_string)?, datetime)
+    }
+
+    /// Renders `datetime` according to a compiled plan. Directives that
+    /// cannot be rendered produce no output.
+    pub fn render<T: Formattable>(&self, plan: &CompiledFormat, datetime: T) -> String {
+        plan.render_with(self, &datetime, false)
+            .expect("lenient rendering does not fail")
+    }
+
+    /// Renders `datetime` according to a compiled plan, failing on the first
+    /// directive that cannot be rendered.
+    pub fn try_render<T: Formattable>(
+        &self,
+        plan: &CompiledFormat,
+        datetime: T,
+    ) -> Result<String, FormatError> {
+        plan.render_with(self, &datetime, true)
+    }
+
+    /// Writes `datetime` formatted according to `format_string` into `out`,
+    /// as [`format`](Self::format) renders it, without building a
+    /// `String`. Only `out` can make this fail.
+    pub fn format_into<W: fmt::Write, T: Formattable>(
+        &self,
+        out: &mut W,
+        format_string: &str,
+        datetime: T,
+    ) -> fmt::Result {
+        self.render_into(out, &CompiledFormat::parse(format_string), datetime)
+    }
+
+    /// Writes `datetime` rendered according to a compiled plan into `out`.
+    /// Directives that cannot be rendered produce no output.
+    pub fn render_into<W: fmt::Write, T: Formattable>(
+        &self,
+        out: &mut W,
+        plan: &CompiledFormat,
+        datetime: T,
+    ) -> fmt::Result {
+        match plan.render_into(self, &datetime, false, out) {
+            Ok(()) => Ok(()),
+            Err(RenderError::Write(error)) => Err(error),
+            Err(RenderError::Format(_)) => unreachable!("lenient rendering does not fail"),
+        }
+    }
+
+    /// Writes `datetime` formatted according to `format_string` into an
+    /// [`io::Write`]. Text is written as it is produced, in several small
+    /// writes, so `out` should be buffered.
+    pub fn format_into_writer<W: io::Write + ?Sized, T: Formattable>(
+        &self,
+        out: &mut W,
+        format_string: &str,
+        datetime: T,
+    ) -> io::Result<()> {
+        let mut adapter = IoAdapter::new(out);
+        self.format_into(&mut adapter, format_string, datetime)
+            .map_err(|fmt::Error| adapter.into_error())
+    }
+
+    /// Pairs `datetime` with a compiled plan for use with `write!` and
+    /// `format!`, rendering with this formatter's names. See
+    /// [`FormatDisplay`].
+    pub fn display<'a, T: Formattable>(
+        &'a self,
+        plan: &'a CompiledFormat,
+        datetime: T,
+    ) -> FormatDisplay<'a, T> {
+        FormatDisplay::new(self, Cow::Borrowed(plan), datetime)
+    }
+
+    /// Lists format strings that render `example` exactly with this
+    /// formatter's names, as [`infer_format`](crate::infer_format) does.
+    pub fn infer_format(&self, example: &str) -> Vec<Candidate> {
+        infer::infer(self, example)
+    }
+
+    /// Explains `format_string` directive by directive, with a sample
+    /// rendering in this formatter's locale.
+    pub fn explain(&self, format_string: &str) -> Explanation {
+        Explanation::new(self, format_string)
+    }
+
+    /// Reads a datetime from `input` laid out according to `format_string`,
+    /// with this formatter's names, as [`parse`](crate::parse) does.
+    pub fn parse(
+        &self,
+        input: &str,
+        format_string: &str,
+    ) -> Result<chrono::DateTime<FixedOffset>, ParseError> {
+        self.parse_compiled(&CompiledFormat::try_parse(format_string)?, input)
+    }
+
+    /// Reads a datetime from `input` laid out according to a compiled
+    /// plan.
+    pub fn parse_compiled(
+        &self,
+        plan: &CompiledFormat,
+        input: &str,
+    ) -> Result<chrono::DateTime<FixedOffset>, ParseError> {
+        parser::parse_with(self, plan, input)
+    }
+
+    /// Looks up the localized name for `%a`, `%A`, `%b`, `%B` or `%h`, or
+    /// the locale's preferred representation for `%c`, `%x` or `%X`.
+    pub(crate) fn localized<T: Formattable + ?Sized>(
+        &self,
+        specifier: char,
+        datetime: &T,
+    ) -> Result<String, DateTimeError> {
+        self.with_localized(specifier, datetime, |value| value.to_string())
+    }
+
+    /// Like [`localized`](Self::localized), but hands the value to `f`
+    /// without copying it into a `String`.
+    pub(crate) fn with_localized<T: Formattable + ?Sized, R>(
+        &self,
+        specifier: char,
+        datetime: &T,
+        f: impl FnOnce(&dyn fmt::Display) -> R,
+    ) -> Result<R, DateTimeError> {
+        let mut bag = components::Bag::default();
+        let options = match specifier {
+            'a' => {
+                bag.weekday = Some(components::Text::Short);
+                DateTimeFormatterOptions::Components(bag)
+            }
+            'A' => {
+                bag.weekday = Some(components::Text::Long);
+                DateTimeFormatterOptions::Components(bag)
+            }
+            'b' | 'h' => {
+                bag.month = Some(components::Month::Short);
+                DateTimeFormatterOptions::Components(bag)
+            }
+            'B' => {
+                bag.month = Some(components::Month::Long);
+                DateTimeFormatterOptions::Components(bag)
+            }
+            'c' | 'x' if datetime.year() <= 0 => {
+                return Ok(f(&self.before_common_era(specifier, datetime)?));
+            }
+            'c' | 'x' | 'X' => preferred_options(specifier),
+            _ => unreachable!("'{specifier}' is not localized"),
+        };
+        let formatter = self.cached_formatter(options)?;
+        let formatted = formatter.format(&icu_date(datetime)?)?;
+        Ok(f(&formatted))
+    }
+
+    /// `%c` or `%x` for a year before 1, which the short lengths would show
+    /// without its era, as if it came after 1. The fields and hour cycle
+    /// the locale's short length resolves to are kept, and the era and the
+    /// full year are added to them.
+    fn before_common_era<T: Formattable + ?Sized>(
+        &self,
+        specifier: char,
+        datetime: &T,
+    ) -> Result<String, DateTimeError> {
+        let mut bag = self
+            .cached_formatter(preferred_options(specifier))?
+            .resolve_components();
+        bag.era = Some(components::Text::Short);
+        bag.year = Some(components::Year::Numeric);
+        let formatter = self.cached_formatter(DateTimeFormatterOptions::Components(bag))?;
+        formatter.format_to_string(&icu_date(datetime)?)
+    }
+
+    /// Whether the locale's calendar counts years in eras of its own, as the
+    /// Japanese and Buddhist calendars do, so that `%EC`, `%Ey` and `%EY`
+    /// differ from `%C`, `%y` and `%Y`.
+    pub(crate) fn has_eras(&self) -> Result<bool, DateTimeError> {
+        Ok(self.era_calendar()?.is_some())
+    }
+
+    /// The year of `datetime` within its era of the locale's calendar, for
+    /// `%Ey`, or `None` if the calendar has no era of its own for the date,
+    /// as for Japanese dates before the Meiji era, which fall back to the
+    /// Gregorian eras.
+    pub(crate) fn era_year<T: Formattable + ?Sized>(
+        &self,
+        datetime: &T,
+    ) -> Result<Option<i32>, DateTimeError> {
+        let Some(calendar) = self.era_calendar()? else {
+            return Ok(None);
+        };
+        let date = Date::try_new_iso_date(
+            datetime.year(),
+            datetime.month() as u8,
+            datetime.day() as u8,
+        )?;
+        let year = date.to_calendar(Ref(calendar)).year();
+        Ok((!matches!(year.era.0.as_str(), "ce" | "bce")).then_some(year.number))
+    }
+
+    /// Hands `f` the era of `datetime` in the locale's calendar for `%EC`,
+    /// or the year with its era for `%EY`, such as `令和6年`.
+    pub(crate) fn with_era<T: Formattable + ?Sized, R>(
+        &self,
+        specifier: char,
+        datetime: &T,
+        f: impl FnOnce(&dyn fmt::Display) -> R,
+    ) -> Result<R, DateTimeError> {
+        let mut bag = components::Bag::default();
+        bag.era = Some(components::Text::Short);
+        if specifier == 'Y' {
+            bag.year = Some(components::Year::Numeric);
+        }
+        let formatter = self.cached_formatter(DateTimeFormatterOptions::Components(bag))?;
+        let formatted = formatter.format(&icu_date(datetime)?)?;
+        Ok(f(&formatted))
+    }
+
+    /// The locale's calendar, built on first use, or `None` for the
+    /// Gregorian calendar.
+    fn era_calendar(&self) -> Result<Option<&AnyCalendar>, DateTimeError> {
+        let calendar = self.calendar.get_or_init(|| {
+            let locale = self.data_locale().into();
+            let calendar = match self.data.provider() {
+                None => AnyCalendar::new_for_locale(&locale),
+                Some(provider) => {
+                    AnyCalendar::try_new_for_locale_unstable(&provider.as_deserializing(), &locale)?
+                }
+            };
+            Ok(match calendar.kind() {
+                AnyCalendarKind::Gregorian | AnyCalendarKind::Iso => None,
+                _ => Some(calendar),
+            })
+        });
+        calendar
+            .as_ref()
+            .map(Option::as_ref)
+            .map_err(|error| *error)
+    }
+
+    /// The digits zero to nine of the locale's numbering system, for `%O`,
+    /// worked out on first use, or `None` if they are the ASCII ones.
+    pub(crate) fn digits(&self) -> Result<Option<&[char; 10]>, DateTimeError> {
+        let digits = self.digits.get_or_init(|| {
+            let locale = self.data_locale().into();
+            let options = Default::default();
+            let formatter = match self.data.provider() {
+                None => FixedDecimalFormatter::try_new(&locale, options),
+                Some(provider) => FixedDecimalFormatter::try_new_unstable(
+                    &provider.as_deserializing(),
+                    &locale,
+                    options,
+                ),
+            }?;
+            let mut digits = ['0'; 10];
+            for (value, digit) in (0_u8..).zip(&mut digits) {
+                let formatted = formatter.format_to_string(&value.into());
+                let mut chars = formatted.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => *digit = c,
+                    _ => return Ok(None),
+                }
+            }
+            Ok((!digits.iter().copied().eq('0'..='9')).then_some(digits))
+        });
+        digits.as_ref().map(Option::as_ref).map_err(|error| *error)
+    }
+
+    /// Returns the ICU formatter for `options` in this formatter's locale,
+    /// building and caching it on first use.
+    fn cached_formatter(
+        &self,
+        options: DateTimeFormatterOptions,
+    ) -> Result<Arc<DateTimeFormatter>, DateTimeError> {
+        let key = (self.locale.clone(), options);
+        if let Some(dtf) = self.read_cache().get(&key) {
+            return Ok(Arc::clone(dtf));
+        }
+
+        let dtf = self.datetime_formatter(self.data_locale(), options)?;
+        let mut cache = self
+            .cache
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+        Ok(Arc::clone(
+            cache.entry(key).or_insert_with(|| Arc::new(dtf)),
+        ))
+    }
+
+    /// The locale ICU data is loaded for: this formatter's locale, or
+    /// English if the data has neither it nor a parent of it. The data is
+    /// asked once and quietly, as ICU logs every failed request in debug
+    /// builds.
+    pub(crate) fn data_locale(&self) -> &Locale {
+        self.data_locale.get_or_init(|| {
+            let mut locale = DataLocale::from(&self.locale);
+            // Every locale with datetime data has Gregorian patterns.
+            locale.set_unicode_ext(key!("ca"), value!("gregory"));
+            let mut metadata = DataRequestMetadata::default();
+            metadata.silent = true;
+            let request = DataRequest {
+                locale: &locale,
+                metadata,
+            };
+            let response = match self.data.provider() {
+                None => {
+                    DataProvider::<DateSkeletonPatternsV1Marker>::load(&Baked, request).map(drop)
+                }
+                Some(provider) => provider
+                    .load_buffer(DateSkeletonPatternsV1Marker::KEY, request)
+                    .map(drop),
+            };
+            match response {
+                Err(DataError {
+                    kind: DataErrorKind::MissingLocale,
+                    ..
+                }) => locale!("en"),
+                _ => self.locale.clone(),
+            }
+        })
+    }
+
+    fn read_cache(&self) -> RwLockReadGuard<'_, HashMap<CacheKey, Arc<DateTimeFormatter>>> {
+        // The cache is only ever extended with complete entries, so it is
+        // still usable after a panic elsewhere poisoned the lock.
+        self.cache.read().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Number of ICU formatters built so far.
+    #[cfg(test)]
+    pub(crate) fn cached_formatters(&self) -> usize {
+        self.read_cache().len()
+    }
+
+    /// Builds an ICU formatter for `locale` from this formatter's data.
+    fn datetime_formatter(
+        &self,
+        locale: &Locale,
+        options: DateTimeFormatterOptions,
+    ) -> Result<DateTimeFormatter, DateTimeError> {
+        match self.data.provider() {
+            None => DateTimeFormatter::try_new_experimental(&locale.into(), options),
+            Some(provider) => DateTimeFormatter::try_new_experimental_unstable(
+                &provider.as_deserializing(),
+                &locale.into(),
+                options,
+            ),
+        }
+    }
+}
+
+/// The lengths of the locale's preferred representations, `%c`, `%x` and
+/// `%X`.
+fn preferred_options(specifier: char) -> DateTimeFormatterOptions {
+    match specifier {
+        'c' => length::Bag::from_date_time_style(length::Date::Short, length::Time::Medium),
+        'x' => length::Bag::from_date_style(length::Date::Short),
+        'X' => length::Bag::from_time_style(length::Time::Medium),
+        _ => unreachable!("'{specifier}' is not a preferred representation"),
+    }
+    .into()
+}
+
+/// Converts `datetime` to an ICU date in the ISO calendar.
+fn icu_date<T: Formattable + ?Sized>(
+    datetime: &T,
+) -> Result<DateTime<icu::calendar::AnyCalendar>, DateTimeError> {
+    let date = DateTime::try_new_iso_datetime(
+        datetime.year(),
+        datetime.month() as u8,
+        datetime.day() as u8,
+        datetime.hour() as u8,
+        datetime.minute() as u8,
+        datetime.second().min(59) as u8,
+    )?;
+    Ok(date.to_any())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,202 @@
+use chrono::{FixedOffset, TimeZone};
+
+use icu::locid::locale;
+
+use crate::{format, posix_locale, CompiledFormat, DataSource, FormatError, Formatter};
+
+#[test]
+fn localized_names() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    let cases = [
+        (locale!("en-US"), "Sat|Saturday|Dec|December"),
+        (locale!("de-DE"), "Sa|Samstag|Dez|Dezember"),
+        (locale!("ja-JP"), "土|土曜日|12月|12月"),
+        (locale!("pt-BR"), "sáb.|sábado|dez.|dezembro"),
+    ];
+    for (locale, expected) in cases {
+        let formatter = Formatter::new(locale);
+        assert_eq!(formatter.format("%a|%A|%b|%B", datetime), expected);
+    }
+}
+
+#[test]
+fn unsupported_locales_fall_back_to_english() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    let formatter = Formatter::new(locale!("tlh"));
+    assert_eq!(
+        formatter.try_format("%A %B", datetime).unwrap(),
+        "Saturday December"
+    );
+    let formatter = Formatter::new(locale!("de-AT"));
+    assert_eq!(formatter.format("%B", datetime), "Dezember");
+}
+
+#[test]
+fn data_locales_are_checked_before_building() {
+    assert_eq!(Formatter::new(locale!("tlh")).data_locale(), &locale!("en"));
+    assert_eq!(
+        Formatter::new(locale!("de-AT")).data_locale(),
+        &locale!("de-AT")
+    );
+
+    let dir = std::env::temp_dir().join(format!("datefmt-en-data-{}", std::process::id()));
+    let skeletons = dir.join("datetime/skeletons@1");
+    std::fs::create_dir_all(&skeletons).unwrap();
+    std::fs::write(dir.join("manifest.json"), r#"{"syntax": "Json"}"#).unwrap();
+    std::fs::write(skeletons.join("en-u-ca-gregory.json"), "{}").unwrap();
+    let data = DataSource::open(&dir).unwrap();
+
+    let formatter = Formatter::new(locale!("de-DE")).with_data(data.clone());
+    assert_eq!(formatter.data_locale(), &locale!("en"));
+    let formatter = Formatter::new(locale!("en-GB")).with_data(data);
+    assert_eq!(formatter.data_locale(), &locale!("en-GB"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn posix_locale_names() {
+    assert_eq!(posix_locale("de_DE.UTF-8@euro"), Some(locale!("de-DE")));
+    assert_eq!(posix_locale("ja_JP.eucJP"), Some(locale!("ja-JP")));
+    assert_eq!(posix_locale("pt_BR"), Some(locale!("pt-BR")));
+    assert_eq!(posix_locale("sr_RS@latin"), Some(locale!("sr-Latn-RS")));
+    assert_eq!(posix_locale("C.UTF-8"), Some(locale!("en-US")));
+    assert_eq!(posix_locale("POSIX"), Some(locale!("en-US")));
+    assert_eq!(posix_locale("not a locale"), None);
+}
+
+#[test]
+fn compiled_data_is_the_default() {
+    assert!(Formatter::default().data().is_compiled());
+    assert_eq!(
+        Formatter::new(locale!("fr")).data(),
+        &DataSource::compiled()
+    );
+}
+
+#[test]
+fn unreadable_runtime_data_is_rejected() {
+    assert!(DataSource::from_dir("/nonexistent/icu4x-data").is_err());
+    assert!(DataSource::open("/nonexistent/icu4x-data.postcard").is_err());
+    assert!(DataSource::from_blob(vec![1, 2, 3]).is_err());
+}
+
+#[test]
+fn missing_runtime_data_is_reported_by_key() {
+    let dir = std::env::temp_dir().join(format!("datefmt-empty-data-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("manifest.json"), r#"{"syntax": "Json"}"#).unwrap();
+    let data = DataSource::open(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    let formatter = Formatter::new(locale!("de-DE")).with_data(data);
+    assert!(!formatter.data().is_compiled());
+    assert!(matches!(
+        formatter.try_format("%Y %A", datetime),
+        Err(FormatError::MissingData { offset: 3, .. })
+    ));
+    assert_eq!(formatter.format("%Y %A", datetime), "2024 ");
+}
+
+#[test]
+fn locale_preferred_representations() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    let cases = [
+        (
+            locale!("en-US"),
+            "12/14/24, 3:04:05\u{202f}PM|12/14/24|3:04:05\u{202f}PM",
+        ),
+        (locale!("fr-FR"), "14/12/2024 15:04:05|14/12/2024|15:04:05"),
+        (locale!("de-DE"), "14.12.24, 15:04:05|14.12.24|15:04:05"),
+        (locale!("ja-JP"), "2024/12/14 15:04:05|2024/12/14|15:04:05"),
+    ];
+    for (locale, expected) in cases {
+        let formatter = Formatter::new(locale);
+        assert_eq!(formatter.format("%c|%x|%X", datetime), expected);
+    }
+    assert_eq!(format("%#c", datetime), "12/14/24, 3:04:05\u{202f}pm");
+
+    // Years before 1 keep their era, so that they do not read as years
+    // after it.
+    let ides = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(-43, 3, 15, 13, 4, 5)
+        .unwrap();
+    let cases = [
+        (
+            locale!("en-US"),
+            "3/15/44 BC, 1:04:05\u{202f}PM|3/15/44 BC|1:04:05\u{202f}PM",
+        ),
+        (
+            locale!("fr-FR"),
+            "15/03/44 av. J.-C. 13:04:05|15/03/44 av. J.-C.|13:04:05",
+        ),
+        (
+            locale!("de-DE"),
+            "15.03.44 v. Chr., 13:04:05|15.03.44 v. Chr.|13:04:05",
+        ),
+        (
+            locale!("ja-JP"),
+            "紀元前44/03/15 13:04:05|紀元前44/03/15|13:04:05",
+        ),
+        // Korean puts the era and the day period first.
+        (locale!("ko"), "BC 44/3/15 PM 1:04:05|BC 44/3/15|PM 1:04:05"),
+    ];
+    for (locale, expected) in cases {
+        let formatter = Formatter::new(locale);
+        assert_eq!(formatter.format("%c|%x|%X", ides), expected);
+    }
+    let year_zero = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(0, 3, 15, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%^x", year_zero), "3/15/1 BC");
+}
+
+#[test]
+fn icu_formatters_are_built_once() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    let formatter = Formatter::new(locale!("fr-FR"));
+    let plan = CompiledFormat::parse("%a %A %b %h %a %B");
+    assert_eq!(formatter.cached_formatters(), 0);
+    let first = formatter.render(&plan, datetime);
+    assert_eq!(formatter.cached_formatters(), 4);
+    for _ in 0..3 {
+        assert_eq!(formatter.render(&plan, datetime), first);
+    }
+    assert_eq!(
+        formatter.clone().format("%x %B", datetime),
+        "14/12/2024 décembre"
+    );
+    assert_eq!(formatter.cached_formatters(), 5);
+}
+
+#[test]
+fn formatters_are_shared_across_threads() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+        .unwrap();
+    let formatter = Formatter::new(locale!("de-DE"));
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| assert_eq!(formatter.format("%A %B", datetime), "Samstag Dezember"));
+        }
+    });
+    assert_eq!(formatter.cached_formatters(), 2);
+}
@@ -0,0 +1,517 @@
+use chrono::{TimeZone, Utc};
+
+use crate::compiled::CompiledFormat;
+use crate::date_input::zone_abbreviation;
+use crate::formatter::Formatter;
+use crate::parser::{longest_match, Names};
+
+/// A format string that renders an example, as returned by
+/// [`infer_format`](crate::infer_format).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The format string, valid input for [`format`](crate::format).
+    pub format: String,
+    /// How likely this is the intended format, from 0 to 1. The
+    /// confidences of all candidates for an example add up to 1.
+    pub confidence: f64,
+}
+
+/// Morning and afternoon markers with the specifier that renders them.
+const MERIDIEMS: [(&str, &str); 4] = [("AM", "%p"), ("PM", "%p"), ("am", "%P"), ("pm", "%P")];
+
+/// Most combinations of field readings tried for one example.
+const MAX_COMBINATIONS: usize = 4096;
+
+/// Lists the format strings that render `example` exactly, most likely
+/// first.
+pub(crate) fn infer(formatter: &Formatter, example: &str) -> Vec<Candidate> {
+    let slots = Slots::new(formatter, example).slots;
+    let day_first = day_first(formatter);
+
+    let mut scored: Vec<(String, f64)> = Vec::new();
+    for choices in combinations(&slots) {
+        // A format without conversions describes no date at all.
+        if repeats_role(&choices) || choices.iter().all(|choice| choice.literal) {
+            continue;
+        }
+        let format: String = choices
+            .iter()
+            .map(|choice| choice.format.as_str())
+            .collect();
+        let check: String = choices.iter().map(|choice| choice.check()).collect();
+        if scored.iter().any(|(known, _)| *known == format) || !renders(formatter, &check, example)
+        {
+            continue;
+        }
+        let order: String = choices.iter().filter_map(|choice| choice.order).collect();
+        let weight: f64 = choices.iter().map(|choice| choice.weight).product();
+        scored.push((format, weight * order_weight(&order, day_first)));
+    }
+
+    let total: f64 = scored.iter().map(|(_, score)| score).sum();
+    let mut candidates: Vec<_> = scored
+        .into_iter()
+        .map(|(format, score)| Candidate {
+            format,
+            confidence: score / total,
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    candidates
+}
+
+/// Whether two choices stand for the same field.
+fn repeats_role(choices: &[&Choice]) -> bool {
+    let mut seen = Vec::new();
+    for &role in choices.iter().flat_map(|choice| choice.roles) {
+        if seen.contains(&role) {
+            return true;
+        }
+        seen.push(role);
+    }
+    false
+}
+
+/// Whether `format_string` reads `example` and renders it back unchanged.
+fn renders(formatter: &Formatter, format_string: &str, example: &str) -> bool {
+    let Ok(plan) = CompiledFormat::try_parse(format_string) else {
+        return false;
+    };
+    formatter
+        .parse_compiled(&plan, example)
+        .is_ok_and(|datetime| formatter.try_render(&plan, datetime).as_deref() == Ok(example))
+}
+
+/// Whether the locale writes the day before the month in numeric dates.
+fn day_first(formatter: &Formatter) -> bool {
+    let sample = Utc.with_ymd_and_hms(2033, 11, 22, 0, 0, 0).unwrap();
+    let date = formatter.format("%x", sample);
+    match (date.find("22"), date.find("11")) {
+        (Some(day), Some(month)) => day < month,
+        _ => true,
+    }
+}
+
+/// How plausible an order of numeric year (`Y`, or `y` with two digits),
+/// month (`M`) and day (`D`) fields is.
+fn order_weight(order: &str, day_first: bool) -> f64 {
+    // Only a four-digit year makes year, month, day win over the locale's
+    // order: "12/11/10" is more likely the locale's date.
+    if order == "yMD" {
+        return 0.5;
+    }
+    match order.replace('y', "Y").as_str() {
+        "" | "Y" | "M" | "D" | "YMD" | "YM" | "MY" => 1.0,
+        "DMY" | "DM" if day_first => 0.6,
+        "MDY" | "MD" if !day_first => 0.6,
+        "DMY" | "DM" | "MDY" | "MD" => 0.4,
+        _ => 0.05,
+    }
+}
+
+/// What a choice stands for; a format uses each role at most once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Year,
+    Month,
+    Day,
+    DayOfYear,
+    Weekday,
+    Hour,
+    Minute,
+    Second,
+    Fraction,
+    Meridiem,
+    Offset,
+    Timestamp,
+}
+
+/// One way to read a piece of the example.
+#[derive(Debug, Clone)]
+struct Choice {
+    /// The format string piece.
+    format: String,
+    /// The piece to check the example against when it differs from
+    /// `format`, as for `%Z`, which only renders the abbreviation with a
+    /// named time zone.
+    check: Option<String>,
+    roles: &'static [Role],
+    /// Whether this is literal text, which later text can be joined to.
+    literal: bool,
+    /// A numeric year, month or day, as `Y` (`y` with two digits), `M` or
+    /// `D`.
+    order: Option<char>,
+    weight: f64,
+}
+
+impl Choice {
+    fn new(format: impl Into<String>, roles: &'static [Role], weight: f64) -> Self {
+        Choice {
+            format: format.into(),
+            check: None,
+            roles,
+            literal: false,
+            order: None,
+            weight,
+        }
+    }
+
+    fn literal(text: &str) -> Self {
+        Choice {
+            literal: true,
+            ..Choice::new(text.replace('%', "%%"), &[], 1.0)
+        }
+    }
+
+    fn ordered(mut self, order: char) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    fn check(&self) -> &str {
+        self.check.as_deref().unwrap_or(&self.format)
+    }
+}
+
+/// The example split into pieces, each with the ways it can be read.
+struct Slots {
+    slots: Vec<Vec<Choice>>,
+    has_meridiem: bool,
+}
+
+impl Slots {
+    fn new(formatter: &Formatter, example: &str) -> Self {
+        let names = names(formatter);
+        let has_meridiem = MERIDIEMS
+            .iter()
+            .any(|&(marker, _)| example.contains(marker));
+        let mut slots = Slots {
+            slots: Vec::new(),
+            has_meridiem,
+        };
+
+        let mut rest = example;
+        while let Some(c) = rest.chars().next() {
+            let length = if c.is_ascii_digit() {
+                slots.digits(rest)
+            } else if matches!(c, '+' | '-') && slots.after_time() {
+                slots
+                    .offset(rest)
+                    .unwrap_or_else(|| slots.literal(&rest[..1]))
+            } else if c.is_alphabetic() {
+                let word = &rest[..rest
+                    .char_indices()
+                    .find(|&(_, c)| !c.is_alphabetic())
+                    .map_or(rest.len(), |(index, _)| index)];
+                let name = longest_match(rest, &names).filter(|&(length, _)| length >= word.len());
+                if let Some((length, (specifier, index))) = name {
+                    slots.name(&rest[..length], specifier, &names[index].0)
+                } else if let Some(&(_, specifier)) =
+                    MERIDIEMS.iter().find(|&&(marker, _)| marker == word)
+                {
+                    slots.push(vec![Choice::new(specifier, &[Role::Meridiem], 1.0)]);
+                    word.len()
+                } else if word.len() > 1 && zone_abbreviation(&word.to_ascii_lowercase()).is_some()
+                {
+                    let mut zone = Choice::new("%Z", &[], 1.0);
+                    zone.check = Some(word.to_owned());
+                    slots.push(vec![zone, Choice::new(word, &[], 0.5)]);
+                    word.len()
+                } else {
+                    slots.literal(word)
+                }
+            } else {
+                slots.literal(&rest[..c.len_utf8()])
+            };
+            rest = &rest[length..];
+        }
+        slots
+    }
+
+    fn push(&mut self, choices: Vec<Choice>) {
+        self.slots.push(choices);
+    }
+
+    /// Adds literal text, joining it to literal text before it.
+    fn literal(&mut self, text: &str) -> usize {
+        match self.slots.last_mut() {
+            Some(last) if is_literal(last) => last[0].format += &text.replace('%', "%%"),
+            _ => self.push(vec![Choice::literal(text)]),
+        }
+        text.len()
+    }
+
+    /// Whether the last field read was part of a time of day, so that a
+    /// sign starts a UTC offset rather than separating date fields.
+    fn after_time(&self) -> bool {
+        let last_field = self.slots.iter().rev().find(|slot| !is_literal(slot));
+        last_field.is_some_and(|slot| {
+            slot.iter().any(|choice| {
+                choice.roles.iter().any(|role| {
+                    matches!(
+                        role,
+                        Role::Hour | Role::Minute | Role::Second | Role::Fraction | Role::Meridiem
+                    )
+                })
+            })
+        })
+    }
+
+    /// Reads a month or weekday name.
+    fn name(&mut self, text: &str, specifier: char, name: &str) -> usize {
+        let case = if text == name {
+            ""
+        } else if text == name.to_uppercase() {
+            "^"
+        } else if text == name.to_lowercase() {
+            "#"
+        } else {
+            return self.literal(text);
+        };
+        let roles: &'static [Role] = match specifier {
+            'a' | 'A' => &[Role::Weekday],
+            _ => &[Role::Month],
+        };
+        self.push(vec![Choice::new(format!("%{case}{specifier}"), roles, 1.0)]);
+        text.len()
+    }
+
+    /// Reads a run of digits, and a time of day when it starts one.
+    fn digits(&mut self, rest: &str) -> usize {
+        let run = digit_run(rest);
+        let after = &rest[run.len()..];
+        if run.len() <= 2 && after.starts_with(':') && digit_run(&after[1..]).len() == 2 {
+            return self.time(rest);
+        }
+        let choices = match run.len() {
+            1 | 2 => {
+                let padded = self.space_padded(run);
+                let mut choices = vec![
+                    Choice::new(numeric('d', run, padded), &[Role::Day], 1.0).ordered('D'),
+                    Choice::new(numeric('m', run, padded), &[Role::Month], 1.0).ordered('M'),
+                ];
+                if run.len() == 2 {
+                    choices.push(Choice::new("%y", &[Role::Year], 0.3).ordered('y'));
+                }
+                if self.has_meridiem && meridiem_follows(after) {
+                    choices.push(Choice::new(numeric('I', run, padded), &[Role::Hour], 2.0));
+                }
+                choices
+            }
+            3 => vec![Choice::new("%j", &[Role::DayOfYear], 1.0)],
+            4 => vec![Choice::new("%Y", &[Role::Year], 1.0).ordered('Y')],
+            6 => vec![
+                Choice::new("%H%M%S", &[Role::Hour, Role::Minute, Role::Second], 1.0),
+                Choice::new("%y%m%d", &[Role::Year, Role::Month, Role::Day], 0.5),
+            ],
+            8 => vec![Choice::new(
+                "%Y%m%d",
+                &[Role::Year, Role::Month, Role::Day],
+                1.0,
+            )],
+            9..=11 => vec![Choice::new("%s", &[Role::Timestamp], 1.0)],
+            13 => vec![Choice::new("%{epoch:ms}", &[Role::Timestamp], 1.0)],
+            16 => vec![Choice::new("%{epoch:us}", &[Role::Timestamp], 1.0)],
+            19 => vec![Choice::new("%{epoch:ns}", &[Role::Timestamp], 1.0)],
+            12 => vec![Choice::new(
+                "%Y%m%d%H%M",
+                &[Role::Year, Role::Month, Role::Day, Role::Hour, Role::Minute],
+                1.0,
+            )],
+            14 => vec![Choice::new(
+                "%Y%m%d%H%M%S",
+                &[
+                    Role::Year,
+                    Role::Month,
+                    Role::Day,
+                    Role::Hour,
+                    Role::Minute,
+                    Role::Second,
+                ],
+                1.0,
+            )],
+            _ => return self.literal(run),
+        };
+        self.push(choices);
+        run.len()
+    }
+
+    /// Reads `H:M`, `H:M:S` and a fraction of a second after it.
+    fn time(&mut self, rest: &str) -> usize {
+        let mut length = 0;
+        let mut fields = Vec::new();
+        for _ in 0..3 {
+            let run = digit_run(&rest[length..]);
+            fields.push(run);
+            length += run.len();
+            let after = &rest[length..];
+            if !(after.starts_with(':') && digit_run(&after[1..]).len() == 2) {
+                break;
+            }
+            length += 1;
+        }
+
+        let hour = fields[0];
+        let padded = self.space_padded(hour);
+        let twelve_hour = hour
+            .parse::<u32>()
+            .is_ok_and(|hour| (1..=12).contains(&hour));
+        let mut hours = vec![Choice::new(
+            numeric('H', hour, padded),
+            &[Role::Hour],
+            if self.has_meridiem { 0.05 } else { 1.0 },
+        )];
+        if twelve_hour {
+            hours.push(Choice::new(
+                numeric('I', hour, padded),
+                &[Role::Hour],
+                if self.has_meridiem { 1.0 } else { 0.15 },
+            ));
+        }
+        self.push(hours);
+        let later: [(&str, &'static [Role]); 2] =
+            [("%M", &[Role::Minute]), ("%S", &[Role::Second])];
+        for (format, roles) in later.into_iter().take(fields.len() - 1) {
+            self.literal(":");
+            self.push(vec![Choice::new(format, roles, 1.0)]);
+        }
+
+        let after = &rest[length..];
+        if fields.len() == 3 && after.starts_with(['.', ',']) {
+            let digits = digit_run(&after[1..]).len();
+            let format = match digits {
+                3 => Some("%3N"),
+                6 => Some("%6N"),
+                9 => Some("%N"),
+                _ => None,
+            };
+            if let Some(format) = format {
+                self.literal(&after[..1]);
+                self.push(vec![Choice::new(format, &[Role::Fraction], 1.0)]);
+                length += 1 + digits;
+            }
+        }
+        length
+    }
+
+    /// Reads a UTC offset such as `+0100`, `-05:30`, `+01:00:00` or `+01`.
+    fn offset(&mut self, rest: &str) -> Option<usize> {
+        let hours = digit_run(&rest[1..]);
+        let (format, length) = match hours.len() {
+            4 => ("%z", 5),
+            2 => {
+                let mut length = 3;
+                let mut colons = 0;
+                while colons < 2
+                    && rest[length..].starts_with(':')
+                    && digit_run(&rest[length + 1..]).len() == 2
+                {
+                    length += 3;
+                    colons += 1;
+                }
+                (["%:::z", "%:z", "%::z"][colons], length)
+            }
+            _ => return None,
+        };
+        let mut choices = vec![Choice::new(format, &[Role::Offset], 1.0)];
+        // %:::z also renders +hh:mm for offsets that are not whole hours.
+        if format == "%:z" {
+            choices.push(Choice::new("%:::z", &[Role::Offset], 0.2));
+        }
+        self.push(choices);
+        Some(length)
+    }
+
+    /// Whether a single digit follows padding spaces, as `%e` renders
+    /// days below 10. The padding space is taken from the literal text
+    /// before it.
+    fn space_padded(&mut self, run: &str) -> bool {
+        if run.len() != 1 {
+            return false;
+        }
+        let at_start = self.slots.len() == 1;
+        match self.slots.last_mut() {
+            Some(last) if is_literal(last) && last[0].format.ends_with("  ") => {
+                last[0].format.pop();
+                true
+            }
+            Some(last) if is_literal(last) && last[0].format == " " && at_start => {
+                self.slots.pop();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_literal(slot: &[Choice]) -> bool {
+    matches!(slot, [choice] if choice.literal)
+}
+
+/// The leading ASCII digits of `text`.
+fn digit_run(text: &str) -> &str {
+    let length = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    &text[..length]
+}
+
+/// Whether an AM or PM marker follows, possibly after a space.
+fn meridiem_follows(text: &str) -> bool {
+    let text = text.strip_prefix(' ').unwrap_or(text);
+    MERIDIEMS
+        .iter()
+        .any(|&(marker, _)| text.starts_with(marker))
+}
+
+/// The directive for a number written as `digits`: unpadded when it has a
+/// single digit, space-padded when spaces come before that digit, and with
+/// the specifier's usual padding otherwise.
+fn numeric(specifier: char, digits: &str, space_padded: bool) -> String {
+    match (digits.len(), space_padded, specifier) {
+        (1, true, 'd') => "%e".to_owned(),
+        (1, true, 'H') => "%k".to_owned(),
+        (1, true, 'I') => "%l".to_owned(),
+        (1, true, _) => format!("%_{specifier}"),
+        (1, false, _) => format!("%-{specifier}"),
+        _ => format!("%{specifier}"),
+    }
+}
+
+/// The locale's month and weekday names, each with the specifier that
+/// renders it and its index in the list.
+fn names(formatter: &Formatter) -> Vec<(String, (char, usize))> {
+    let Ok(names) = Names::load(formatter, 0) else {
+        return Vec::new();
+    };
+    let months = names.months.into_iter().map(|(name, _)| name);
+    let weekdays = names.weekdays.into_iter().map(|(name, _)| name);
+    months
+        .zip(['B', 'b'].into_iter().cycle())
+        .chain(weekdays.zip(['A', 'a'].into_iter().cycle()))
+        .enumerate()
+        .map(|(index, (name, specifier))| (name, (specifier, index)))
+        .collect()
+}
+
+/// Every combination of one choice per slot, up to [`MAX_COMBINATIONS`].
+fn combinations(slots: &[Vec<Choice>]) -> Vec<Vec<&Choice>> {
+    let mut combinations = vec![Vec::new()];
+    for slot in slots {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination: Vec<&Choice>| {
+                slot.iter().map(move |choice| {
+                    let mut combination = combination.clone();
+                    combination.push(choice);
+                    combination
+                })
+            })
+            .take(MAX_COMBINATIONS)
+            .collect();
+    }
+    combinations
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+use icu::locid::locale;
+
+use crate::{format, Locale, NamedFormat};
+
+#[test]
+fn infer_format_candidates() {
+    let formats = |example: &str, locale: Locale| -> Vec<String> {
+        crate::infer_format(example, &locale)
+            .into_iter()
+            .map(|candidate| candidate.format)
+            .collect()
+    };
+    assert_eq!(
+        formats("05/03/2024", locale!("en-US")),
+        ["%m/%d/%Y", "%d/%m/%Y"]
+    );
+    assert_eq!(
+        formats("05/03/2024", locale!("en-GB")),
+        ["%d/%m/%Y", "%m/%d/%Y"]
+    );
+    assert_eq!(formats("25/03/2024", locale!("en-US")), ["%d/%m/%Y"]);
+    // With a two-digit year the locale decides the order.
+    assert_eq!(
+        formats("12/11/10", locale!("en-US"))[..3],
+        ["%m/%d/%y", "%y/%m/%d", "%d/%m/%y"]
+    );
+    assert_eq!(
+        formats("12/11/10", locale!("en-GB"))[..3],
+        ["%d/%m/%y", "%y/%m/%d", "%m/%d/%y"]
+    );
+    assert_eq!(formats("2012/11/10", locale!("en-US"))[0], "%Y/%m/%d");
+    assert_eq!(
+        formats("2024-03-05 7:08 pm", Locale::UND)[0],
+        "%Y-%m-%d %-I:%M %P"
+    );
+    assert_eq!(
+        formats("2024-03-25 19:08", Locale::UND)[0],
+        "%Y-%m-%d %H:%M"
+    );
+    assert_eq!(formats("5. März 2024", locale!("de-DE")), ["%-d. %B %Y"]);
+    assert_eq!(formats("1709618889", Locale::UND), ["%s"]);
+    assert_eq!(
+        formats("2024-03-25T19:08:09.120Z", Locale::UND)[0],
+        "%Y-%m-%dT%H:%M:%S.%3NZ"
+    );
+    assert!(formats("", Locale::UND).is_empty());
+
+    let candidates = crate::infer_format("Tue, 05 Mar 2024 07:08:09 +0100", &locale!("en-US"));
+    assert_eq!(candidates[0].format, NamedFormat::RfcEmail.format_string());
+    let total: f64 = candidates
+        .iter()
+        .map(|candidate| candidate.confidence)
+        .sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!(candidates
+        .windows(2)
+        .all(|pair| pair[0].confidence >= pair[1].confidence));
+    for candidate in candidates {
+        let datetime = crate::parse("Tue, 05 Mar 2024 07:08:09 +0100", &candidate.format).unwrap();
+        assert_eq!(
+            format(&candidate.format, datetime),
+            "Tue, 05 Mar 2024 07:08:09 +0100"
+        );
+    }
+}
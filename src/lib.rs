@@ -0,0 +1,260 @@
+//! GNU `date`-compatible datetime formatting.
+//!
+//! `datefmt` renders a [`chrono::DateTime`], or any other
+//! [`Formattable`] value, through the `%`-specifier language understood
+//! by coreutils `date` and `strftime(3)`, including the GNU padding and
+//! case flags (`-`, `_`, `0`, `^`, `#`) and field widths. chrono's `%.3f`
+//! fractions are understood too, as are `%{epoch:ms}`, `%{epoch:us}` and
+//! `%{epoch:ns}` for milliseconds, microseconds and nanoseconds since the
+//! epoch, optionally with a fraction as in `%{epoch:ms.3}`. Month and
+//! weekday names are produced by ICU4X, in English by default or in any
+//! locale through a [`Formatter`], from compiled CLDR data or from a
+//! [`DataSource`] loaded at runtime. The POSIX `E` and `O` modifiers
+//! count years in the eras of the locale's calendar, as `%EY` does in
+//! `ja-JP-u-ca-japanese`, and write numbers in the locale's digits, as
+//! `%Od` does in `ar-EG`. [`parse`] reads datetimes back with the same
+//! format strings.
+//!
+//! ```
+//! use chrono::{FixedOffset, TimeZone};
+//!
+//! let datetime = FixedOffset::east_opt(0)
+//!     .unwrap()
+//!     .with_ymd_and_hms(2024, 12, 14, 9, 3, 11)
+//!     .unwrap();
+//! assert_eq!(datefmt::format("%Y-%m-%d %H:%M", datetime), "2024-12-14 09:03");
+//! ```
+//!
+//! Any chrono time zone can be formatted. With an IANA zone from the
+//! bundled database ([`Tz`]), `%Z` prints the abbreviation in effect at
+//! that instant:
+//!
+//! ```
+//! use chrono::TimeZone;
+//! use datefmt::Tz;
+//!
+//! let berlin: Tz = "Europe/Berlin".parse().unwrap();
+//! let summer = berlin.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap();
+//! assert_eq!(datefmt::format("%H:%M %Z (%z)", summer), "12:00 CEST (+0200)");
+//! ```
+
+mod compiled;
+mod data;
+mod date_input;
+mod directive;
+mod display;
+mod error;
+mod explain;
+mod formattable;
+mod formatter;
+mod infer;
+mod lint;
+mod locale;
+mod named;
+mod output;
+mod parser;
+#[cfg(test)]
+mod tests;
+mod zone;
+
+use core::fmt;
+
+use chrono::FixedOffset;
+
+pub use compiled::CompiledFormat;
+pub use data::DataSource;
+pub use date_input::parse_date;
+pub use directive::{Directive, EpochUnit, Item, Modifier};
+pub use display::FormatDisplay;
+pub use error::{DateInputError, FormatError, ParseError};
+pub use explain::{DirectiveExplanation, Explanation, Part};
+pub use formattable::Formattable;
+pub use formatter::Formatter;
+pub use infer::Candidate;
+pub use lint::{Dialect, Lint, LintKind};
+pub use locale::{locale_from_env, posix_locale};
+pub use named::NamedFormat;
+pub use output::{Case, FormattedOutput, Padding};
+pub use zone::{parse_posix_zone, parse_zone, zone_from_env, PosixZone};
+
+/// Locale identifiers, as accepted by [`Formatter::new`].
+pub use icu::locid::Locale;
+
+/// The error returned when ICU locale data cannot be loaded.
+pub use icu_provider::DataError;
+
+/// IANA time zones from the bundled tz database, for zone-aware input.
+pub use chrono_tz::Tz;
+
+/// Formats `datetime` according to `format_string`.
+///
+/// The format string uses the conversion specifiers of GNU `date`, e.g.
+/// `%Y-%m-%d`. Sections that are not a valid conversion are copied to the
+/// output as written.
+///
+/// `datetime` is any [`Formattable`] value: a chrono `DateTime` in any
+/// zone, a naive chrono value, a [`SystemTime`](std::time::SystemTime), or
+/// a `time` or `jiff` value with those features enabled.
+pub fn format<T: Formattable>(format_string: &str, datetime: T) -> String {
+    CompiledFormat::parse(format_string).render(datetime)
+}
+
+/// Formats `datetime` according to `format_string`, rejecting invalid
+/// conversions instead of copying them to the output.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use datefmt::FormatError;
+///
+/// let datetime = FixedOffset::east_opt(0)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 0, 0, 0)
+///     .unwrap();
+/// assert_eq!(
+///     datefmt::try_format("%Y-%Q", datetime),
+///     Err(FormatError::UnknownSpecifier { specifier: 'Q', offset: 4 })
+/// );
+/// ```
+pub fn try_format<T: Formattable>(format_string: &str, datetime: T) -> Result<String, FormatError> {
+    CompiledFormat::try_parse(format_string)?.try_render(datetime)
+}
+
+/// Reads a datetime from `input` laid out according to `format_string`,
+/// the reverse of [`format`], with English names.
+///
+/// Every conversion of [`format`] is understood. Numbers may be padded
+/// with spaces or zeros or not at all, and take up at most their usual
+/// width, so fields can follow each other without separators as in
+/// `%Y%m%d`. Names and `%p` are matched ignoring case, whitespace in the
+/// format matches any amount of whitespace, and the whole input must be
+/// used. Fields missing from the format are taken from
+/// 1970-01-01 00:00:00 UTC, and `%s` takes precedence over the others.
+///
+/// A datetime with a fixed offset survives a round trip through a format
+/// that holds all of its fields with the full year, from `%Y`, `%F` or
+/// `%s`. A two-digit `%y` is read as 1969 to 2068, and `%c` and `%x` show
+/// years that way too, so year 1 reads back as 2001 and year 12345 as 2045:
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+///
+/// let format = "%a %d %b %Y %r %:z";
+/// let datetime = FixedOffset::east_opt(-12600)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+///     .unwrap();
+/// let text = datefmt::format(format, datetime);
+/// assert_eq!(text, "Sat 14 Dec 2024  3:04:05 PM -03:30");
+/// assert_eq!(datefmt::parse(&text, format), Ok(datetime));
+/// ```
+///
+/// Use [`Formatter::parse`] for names in other locales.
+pub fn parse(
+    input: &str,
+    format_string: &str,
+) -> Result<chrono::DateTime<FixedOffset>, ParseError> {
+    Formatter::shared_default().parse(input, format_string)
+}
+
+/// Explains `format_string` directive by directive, with a sample
+/// rendering in English. See [`Explanation`].
+pub fn explain(format_string: &str) -> Explanation {
+    Formatter::shared_default().explain(format_string)
+}
+
+/// Writes `datetime` formatted according to `format_string` into `out`,
+/// as [`format`] renders it, without building a `String`.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+///
+/// let datetime = FixedOffset::east_opt(0)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 9, 3, 11)
+///     .unwrap();
+/// let mut line = String::from("at ");
+/// datefmt::format_into(&mut line, "%H:%M", datetime).unwrap();
+/// assert_eq!(line, "at 09:03");
+/// ```
+pub fn format_into<W: fmt::Write, T: Formattable>(
+    out: &mut W,
+    format_string: &str,
+    datetime: T,
+) -> fmt::Result {
+    Formatter::shared_default().format_into(out, format_string, datetime)
+}
+
+/// Writes `datetime` formatted according to `format_string` into an
+/// [`io::Write`](std::io::Write), as [`format`] renders it. Text is
+/// written as it is produced, so `out` should be buffered.
+pub fn format_into_writer<W: std::io::Write + ?Sized, T: Formattable>(
+    out: &mut W,
+    format_string: &str,
+    datetime: T,
+) -> std::io::Result<()> {
+    Formatter::shared_default().format_into_writer(out, format_string, datetime)
+}
+
+/// Pairs `datetime` with `format_string` for use with `write!` and
+/// `format!`, rendering as [`format`] does when displayed.
+///
+/// The format string is parsed once, here; use
+/// [`CompiledFormat::display`] to reuse a parsed format across datetimes.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+///
+/// let datetime = FixedOffset::east_opt(0)
+///     .unwrap()
+///     .with_ymd_and_hms(2024, 12, 14, 9, 3, 11)
+///     .unwrap();
+/// let line = format!("{} GET /", datefmt::display("%FT%T%:z", datetime));
+/// assert_eq!(line, "2024-12-14T09:03:11+00:00 GET /");
+/// ```
+pub fn display<T: Formattable>(format_string: &str, datetime: T) -> FormatDisplay<'static, T> {
+    FormatDisplay::new(
+        Formatter::shared_default(),
+        std::borrow::Cow::Owned(CompiledFormat::parse(format_string)),
+        datetime,
+    )
+}
+
+/// Lists format strings that render `example` exactly, with the names of
+/// `locale`, most likely first.
+///
+/// Ambiguous fields, such as the order of day and month in `05/03/2024`
+/// or a 12- or 24-hour clock without AM or PM, give several candidates,
+/// ranked using the locale's conventions. Every candidate is checked by
+/// reading the example with it and formatting the result again.
+///
+/// ```
+/// let en_us = "en-US".parse().unwrap();
+///
+/// let candidates = datefmt::infer_format("14/Dec/2024:09:03:11 +0100", &en_us);
+/// assert_eq!(candidates[0].format, "%d/%b/%Y:%H:%M:%S %z");
+///
+/// let candidates = datefmt::infer_format("05/03/2024", &en_us);
+/// let formats: Vec<_> = candidates.iter().map(|c| c.format.as_str()).collect();
+/// assert_eq!(formats, ["%m/%d/%Y", "%d/%m/%Y"]);
+/// assert!(candidates[0].confidence > candidates[1].confidence);
+/// ```
+pub fn infer_format(example: &str, locale: &Locale) -> Vec<Candidate> {
+    Formatter::new(locale.clone()).infer_format(example)
+}
+
+/// Checks `format_string` for sections that are not conversions,
+/// conversions some [`Dialect`]s lack, fields that are ambiguous when read
+/// back, and likely mistakes such as `%M` for the month. See [`Lint`].
+///
+/// ```
+/// use datefmt::LintKind;
+///
+/// assert!(datefmt::lint("%Y-%m-%dT%H:%M:%S%z").is_empty());
+///
+/// let lints = datefmt::lint("%d.%M.%y");
+/// let kinds: Vec<_> = lints.iter().map(|lint| (lint.text.as_str(), &lint.kind)).collect();
+/// assert!(matches!(kinds[..], [("%M", LintKind::Mistake(_)), ("%y", LintKind::Ambiguous(_))]));
+/// ```
+pub fn lint(format_string: &str) -> Vec<Lint> {
+    lint::lint(format_string)
+}
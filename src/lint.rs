@@ -0,0 +1,252 @@
+use core::fmt;
+
+use crate::directive::{self, Directive, Item, Section};
+use crate::error::FormatError;
+
+/// A `strftime` implementation a format string may be meant for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// POSIX.1-2008 `strftime`.
+    Posix,
+    /// The GNU C library.
+    Glibc,
+    /// FreeBSD and macOS.
+    Bsd,
+    /// The musl C library.
+    Musl,
+    /// Rust's `chrono::format::strftime`.
+    Chrono,
+}
+
+impl Dialect {
+    /// Every dialect, in the order lints list them.
+    pub const ALL: [Dialect; 5] = [
+        Dialect::Posix,
+        Dialect::Glibc,
+        Dialect::Bsd,
+        Dialect::Musl,
+        Dialect::Chrono,
+    ];
+
+    /// Whether the dialect renders the conversion `text`, such as `%_5d`,
+    /// the way `date` does.
+    fn supports(self, text: &str, directive: &Directive) -> bool {
+        let (specifiers, flags, width, colons) = match self {
+            Dialect::Posix => (POSIX, "0+", true, 0),
+            Dialect::Glibc => (POSIX_AND_GNU, "-_0^#", true, 0),
+            Dialect::Bsd => (POSIX_AND_BSD, "-_0", false, 0),
+            Dialect::Musl => (POSIX_AND_MUSL, "-_0+", true, 0),
+            // chrono's %:::z shows only the hours.
+            Dialect::Chrono => (POSIX_AND_CHRONO, "-_0", false, 2),
+        };
+        let mut given = text[1..]
+            .chars()
+            .take_while(|c| matches!(c, '#' | '-' | '_' | '^' | '+' | '0'));
+        specifiers.contains(directive.specifier)
+            && given.all(|flag| flags.contains(flag))
+            && (width || directive.width.is_none())
+            && directive.colons <= colons
+            // chrono's %.f takes 3, 6 or 9 digits.
+            && directive.precision.is_none_or(|precision| precision.is_multiple_of(3))
+            && directive.epoch_unit.is_none()
+            && (directive.modifier.is_none() || self != Dialect::Chrono)
+    }
+}
+
+/// The conversions POSIX.1-2008 specifies.
+const POSIX: &str = "%aAbBcCdDeFgGhHIjmMnprRStTuUVwWxXyYzZ";
+/// POSIX, `%k`, `%l`, `%P` and `%s`, as in glibc and chrono.
+const POSIX_AND_GNU: &str = "%aAbBcCdDeFgGhHIjmMnprRStTuUVwWxXyYzZklPs";
+/// POSIX and glibc's additions, and `%.f`.
+const POSIX_AND_CHRONO: &str = "%aAbBcCdDeFgGhHIjmMnprRStTuUVwWxXyYzZklPsf";
+/// POSIX, `%k`, `%l` and `%s`.
+const POSIX_AND_BSD: &str = "%aAbBcCdDeFgGhHIjmMnprRStTuUVwWxXyYzZkls";
+/// POSIX and `%s`.
+const POSIX_AND_MUSL: &str = "%aAbBcCdDeFgGhHIjmMnprRStTuUVwWxXyYzZs";
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dialect::Posix => "POSIX",
+            Dialect::Glibc => "glibc",
+            Dialect::Bsd => "BSD",
+            Dialect::Musl => "musl",
+            Dialect::Chrono => "chrono",
+        })
+    }
+}
+
+/// A problem found in a format string by [`lint`](crate::lint).
+///
+/// ```
+/// use datefmt::{Dialect, LintKind};
+///
+/// let lints = datefmt::lint("%Y-%m-%d %H:%M %P");
+/// assert_eq!(lints.len(), 1);
+/// assert_eq!(lints[0].text, "%P");
+/// assert_eq!(
+///     lints[0].kind,
+///     LintKind::NotPortable {
+///         unsupported: vec![Dialect::Posix, Dialect::Bsd, Dialect::Musl]
+///     }
+/// );
+/// assert_eq!(
+///     lints[0].to_string(),
+///     "%P (byte 15): not supported by POSIX, BSD, musl"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    /// The section of the format string the lint is about, e.g. `%_5d`.
+    pub text: String,
+    /// Byte offset of `text` in the format string.
+    pub offset: usize,
+    /// What is wrong with it.
+    pub kind: LintKind,
+}
+
+/// The kinds of problem [`lint`](crate::lint) reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintKind {
+    /// A section that starts with `%` but is not a valid conversion, and
+    /// is copied to the output as `output`.
+    Unknown { error: FormatError, output: String },
+    /// A conversion, flag or width that the listed dialects do not
+    /// support.
+    NotPortable { unsupported: Vec<Dialect> },
+    /// A conversion whose output can be read back more than one way.
+    Ambiguous(&'static str),
+    /// A conversion that is probably not the one intended.
+    Mistake(&'static str),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (byte {}): ", self.text, self.offset)?;
+        match &self.kind {
+            LintKind::Unknown { output, .. } => {
+                write!(f, "not a conversion, copied as {output:?}")
+            }
+            LintKind::NotPortable { unsupported } => {
+                f.write_str("not supported by ")?;
+                for (index, dialect) in unsupported.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{dialect}")?;
+                }
+                Ok(())
+            }
+            LintKind::Ambiguous(reason) | LintKind::Mistake(reason) => f.write_str(reason),
+        }
+    }
+}
+
+/// A conversion with the text it was written as and the literal text
+/// between it and the conversion before it.
+struct Conversion<'a> {
+    text: &'a str,
+    directive: Directive,
+    before: String,
+}
+
+/// Checks `format_string`, returning the problems found in the order they
+/// occur.
+pub(crate) fn lint(format_string: &str) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let mut conversions = Vec::new();
+    let mut literal = String::new();
+    for (offset, text, section) in directive::parse_sections(format_string) {
+        match section {
+            Section::Invalid { error, output } => {
+                lints.push(Lint {
+                    text: text.to_owned(),
+                    offset,
+                    kind: LintKind::Unknown { error, output },
+                });
+                literal.clear();
+            }
+            Section::Valid {
+                directive_text,
+                items,
+            } => {
+                for item in items {
+                    match item {
+                        Item::Literal(text) => literal += &text,
+                        Item::Directive(directive) => conversions.push(Conversion {
+                            text: directive_text,
+                            directive,
+                            before: std::mem::take(&mut literal),
+                        }),
+                    }
+                }
+            }
+        }
+    }
+
+    let has = |specifiers: &str| {
+        conversions
+            .iter()
+            .any(|conversion| specifiers.contains(conversion.directive.specifier))
+    };
+    for (index, conversion) in conversions.iter().enumerate() {
+        let directive = &conversion.directive;
+        let lint = |kind| Lint {
+            text: conversion.text.to_owned(),
+            offset: directive.offset,
+            kind,
+        };
+
+        let unsupported: Vec<_> = Dialect::ALL
+            .into_iter()
+            .filter(|dialect| !dialect.supports(conversion.text, directive))
+            .collect();
+        if !unsupported.is_empty() {
+            lints.push(lint(LintKind::NotPortable { unsupported }));
+        }
+
+        let previous = index
+            .checked_sub(1)
+            .map(|index| conversions[index].directive.specifier);
+        let after = |specifiers: &str, literal: &str| {
+            previous.is_some_and(|previous| specifiers.contains(previous))
+                && conversion.before == literal
+        };
+        let problem = match directive.specifier {
+            'D' => Some(LintKind::Ambiguous(
+                "month/day/year order, read as day/month/year in most of the world",
+            )),
+            'U' | 'W' => Some(LintKind::Ambiguous(
+                "week of the year that differs from the ISO 8601 week number %V",
+            )),
+            'V' if !has("Gg") => Some(LintKind::Mistake(
+                "ISO 8601 week number without the week-based year %G",
+            )),
+            'G' | 'g' if !has("V") => Some(LintKind::Mistake(
+                "ISO 8601 week-based year without %V; the calendar year is %Y",
+            )),
+            'y' | 'g' => Some(LintKind::Ambiguous(
+                "two-digit year: the century is lost and has to be guessed when read",
+            )),
+            'I' | 'l' if !has("pPr") => {
+                Some(LintKind::Mistake("12-hour clock without AM or PM (%p)"))
+            }
+            'M' if !has("HIklRTrcXmbBhD") && has("YyCdeFj") => Some(LintKind::Mistake(
+                "minute in a date without an hour; the month is %m",
+            )),
+            'm' if after("HIkl", ":") => Some(LintKind::Mistake(
+                "month after an hour and ':'; the minute is %M",
+            )),
+            's' if after("MmR", ":") => Some(LintKind::Mistake(
+                "seconds since the epoch after a minute and ':'; the second is %S",
+            )),
+            _ => None,
+        };
+        lints.extend(problem.map(lint));
+    }
+    lints.sort_by_key(|lint| lint.offset);
+    lints
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,68 @@
+use crate::{FormatError, LintKind};
+
+#[test]
+fn lint_format_strings() {
+    let lints = |format_string: &str| -> Vec<String> {
+        crate::lint(format_string)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    };
+    assert!(lints("%a, %d %b %Y %H:%M:%S %z").is_empty());
+    assert!(lints("%G-W%V-%u").is_empty());
+    assert_eq!(
+        lints("%-d %k %_5e %^B %+4Y %:z %N %q %Q"),
+        [
+            "%-d (byte 0): not supported by POSIX",
+            "%k (byte 4): not supported by POSIX, musl",
+            "%_5e (byte 7): not supported by POSIX, BSD, chrono",
+            "%^B (byte 12): not supported by POSIX, BSD, musl, chrono",
+            "%+4Y (byte 16): not supported by glibc, BSD, chrono",
+            "%:z (byte 21): not supported by POSIX, glibc, BSD, musl",
+            "%N (byte 25): not supported by POSIX, glibc, BSD, musl, chrono",
+            "%q (byte 28): not supported by POSIX, glibc, BSD, musl, chrono",
+            "%Q (byte 31): not a conversion, copied as \"%Q\"",
+        ]
+    );
+    assert_eq!(
+        lints("%D %y %U %W"),
+        [
+            "%D (byte 0): month/day/year order, read as day/month/year in most of the world",
+            "%y (byte 3): two-digit year: the century is lost and has to be guessed when read",
+            "%U (byte 6): week of the year that differs from the ISO 8601 week number %V",
+            "%W (byte 9): week of the year that differs from the ISO 8601 week number %V",
+        ]
+    );
+    assert_eq!(
+        lints("%Y-%M-%d"),
+        ["%M (byte 3): minute in a date without an hour; the month is %m"]
+    );
+    assert_eq!(
+        lints("%Y-%m-%d %H:%m:%s"),
+        [
+            "%m (byte 12): month after an hour and ':'; the minute is %M",
+            "%s (byte 15): not supported by POSIX",
+            "%s (byte 15): seconds since the epoch after a minute and ':'; the second is %S",
+        ]
+    );
+    assert_eq!(
+        lints("%G-%m-%d %I:%M"),
+        [
+            "%G (byte 0): ISO 8601 week-based year without %V; the calendar year is %Y",
+            "%I (byte 9): 12-hour clock without AM or PM (%p)",
+        ]
+    );
+    assert_eq!(
+        lints("%Y-W%V"),
+        ["%V (byte 4): ISO 8601 week number without the week-based year %G"]
+    );
+
+    let unknown = &crate::lint("%Y%")[0];
+    assert_eq!(
+        unknown.kind,
+        LintKind::Unknown {
+            error: FormatError::DanglingPercent { offset: 2 },
+            output: "%".to_owned(),
+        }
+    );
+}
@@ -0,0 +1,43 @@
+use std::env;
+
+use icu::locid::{locale, subtags, Locale};
+
+/// Resolves the locale for dates from the POSIX environment.
+///
+/// `LC_ALL`, `LC_TIME` and `LANG` are consulted in that order and the
+/// first non-empty one is used. A value that is missing, `C`, `POSIX` or
+/// not understood resolves to `en-US`.
+pub fn locale_from_env() -> Locale {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| posix_locale(&value))
+        .unwrap_or(locale!("en-US"))
+}
+
+/// Converts a POSIX locale name such as `de_DE.UTF-8@euro` to a locale
+/// (`de-DE`).
+///
+/// The codeset is dropped, as are modifiers other than the `latin`,
+/// `cyrillic` and `devanagari` script modifiers. `C` and `POSIX` are
+/// `en-US`. Returns `None` if the name is not a valid locale.
+pub fn posix_locale(name: &str) -> Option<Locale> {
+    let (name, modifier) = name.split_once('@').unwrap_or((name, ""));
+    let name = name.split_once('.').map_or(name, |(name, _codeset)| name);
+    if name == "C" || name == "POSIX" {
+        return Some(locale!("en-US"));
+    }
+
+    let mut locale: Locale = name.replace('_', "-").parse().ok()?;
+    let script: Option<subtags::Script> = match modifier {
+        "latin" => "Latn".parse().ok(),
+        "cyrillic" => "Cyrl".parse().ok(),
+        "devanagari" => "Deva".parse().ok(),
+        _ => None,
+    };
+    if script.is_some() {
+        locale.id.script = script;
+    }
+    Some(locale)
+}
@@ -0,0 +1,132 @@
+use crate::compiled::CompiledFormat;
+use crate::formattable::Formattable;
+
+/// The output formats GNU `date` selects with `--iso-8601` (`-I`),
+/// `--rfc-email` (`-R`) and `--rfc-3339`.
+///
+/// Each is a fixed format string in the specifier language and renders
+/// exactly as coreutils does for the same instant and zone, with English
+/// names whatever the locale.
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use datefmt::NamedFormat;
+///
+/// let datetime = FixedOffset::east_opt(19800)
+///     .unwrap()
+///     .with_ymd_and_hms(2023, 11, 15, 3, 43, 20)
+///     .unwrap();
+/// assert_eq!(
+///     NamedFormat::IsoSeconds.format(datetime),
+///     "2023-11-15T03:43:20+05:30"
+/// );
+/// assert_eq!(
+///     NamedFormat::RfcEmail.format(datetime),
+///     "Wed, 15 Nov 2023 03:43:20 +0530"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedFormat {
+    /// `-Idate`: `2023-11-15`.
+    IsoDate,
+    /// `-Ihours`: `2023-11-15T03+05:30`.
+    IsoHours,
+    /// `-Iminutes`: `2023-11-15T03:43+05:30`.
+    IsoMinutes,
+    /// `-Iseconds`: `2023-11-15T03:43:20+05:30`.
+    IsoSeconds,
+    /// `-Ins`: `2023-11-15T03:43:20,123456789+05:30`.
+    IsoNanoseconds,
+    /// `-R`: `Wed, 15 Nov 2023 03:43:20 +0530`.
+    RfcEmail,
+    /// `--rfc-3339=date`: `2023-11-15`.
+    Rfc3339Date,
+    /// `--rfc-3339=seconds`: `2023-11-15 03:43:20+05:30`.
+    Rfc3339Seconds,
+    /// `--rfc-3339=ns`: `2023-11-15 03:43:20.123456789+05:30`.
+    Rfc3339Nanoseconds,
+}
+
+/// The precisions `--iso-8601` accepts, in the order coreutils lists them.
+const ISO_8601: &[(&str, NamedFormat)] = &[
+    ("hours", NamedFormat::IsoHours),
+    ("minutes", NamedFormat::IsoMinutes),
+    ("date", NamedFormat::IsoDate),
+    ("seconds", NamedFormat::IsoSeconds),
+    ("ns", NamedFormat::IsoNanoseconds),
+];
+
+/// The precisions `--rfc-3339` accepts.
+const RFC_3339: &[(&str, NamedFormat)] = &[
+    ("date", NamedFormat::Rfc3339Date),
+    ("seconds", NamedFormat::Rfc3339Seconds),
+    ("ns", NamedFormat::Rfc3339Nanoseconds),
+];
+
+impl NamedFormat {
+    /// The `--iso-8601` format for `precision`, one of `date`, `hours`,
+    /// `minutes`, `seconds` or `ns`, or an unambiguous abbreviation.
+    pub fn iso_8601(precision: &str) -> Option<Self> {
+        lookup(ISO_8601, precision)
+    }
+
+    /// The `--rfc-3339` format for `precision`, one of `date`, `seconds`
+    /// or `ns`, or an unambiguous abbreviation.
+    pub fn rfc_3339(precision: &str) -> Option<Self> {
+        lookup(RFC_3339, precision)
+    }
+
+    /// The precision words `--iso-8601` accepts.
+    pub fn iso_8601_precisions() -> impl Iterator<Item = &'static str> {
+        ISO_8601.iter().map(|&(word, _)| word)
+    }
+
+    /// The precision words `--rfc-3339` accepts.
+    pub fn rfc_3339_precisions() -> impl Iterator<Item = &'static str> {
+        RFC_3339.iter().map(|&(word, _)| word)
+    }
+
+    /// The format string this format stands for.
+    pub fn format_string(self) -> &'static str {
+        match self {
+            NamedFormat::IsoDate | NamedFormat::Rfc3339Date => "%Y-%m-%d",
+            NamedFormat::IsoHours => "%Y-%m-%dT%H%:z",
+            NamedFormat::IsoMinutes => "%Y-%m-%dT%H:%M%:z",
+            NamedFormat::IsoSeconds => "%Y-%m-%dT%H:%M:%S%:z",
+            NamedFormat::IsoNanoseconds => "%Y-%m-%dT%H:%M:%S,%N%:z",
+            NamedFormat::RfcEmail => "%a, %d %b %Y %H:%M:%S %z",
+            NamedFormat::Rfc3339Seconds => "%Y-%m-%d %H:%M:%S%:z",
+            NamedFormat::Rfc3339Nanoseconds => "%Y-%m-%d %H:%M:%S.%N%:z",
+        }
+    }
+
+    /// Parses [`format_string`](Self::format_string) into a plan.
+    pub fn compile(self) -> CompiledFormat {
+        CompiledFormat::parse(self.format_string())
+    }
+
+    /// Formats `datetime` in this format.
+    pub fn format<T: Formattable>(self, datetime: T) -> String {
+        // The plan renders with the shared English formatter, which gives
+        // the C locale's names that RFC 5322 requires.
+        self.compile().render(datetime)
+    }
+}
+
+/// Looks `word` up in `table` the way `argmatch` does: an exact match or an
+/// abbreviation of exactly one entry.
+fn lookup(table: &[(&str, NamedFormat)], word: &str) -> Option<NamedFormat> {
+    if let Some(&(_, format)) = table.iter().find(|&&(name, _)| name == word) {
+        return Some(format);
+    }
+    let mut matches = table
+        .iter()
+        .filter(|&&(name, _)| !word.is_empty() && name.starts_with(word));
+    match (matches.next(), matches.next()) {
+        (Some(&(_, format)), None) => Some(format),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,71 @@
+use crate::{NamedFormat, Tz};
+
+#[test]
+fn named_formats_match_coreutils() {
+    let instant = chrono::DateTime::from_timestamp(-1234567891, 999_999_500).unwrap();
+    let expected = [
+        (
+            Tz::Asia__Kolkata,
+            [
+                "1930-11-18T05+05:30",
+                "1930-11-18T05:58:29,999999500+05:30",
+                "Tue, 18 Nov 1930 05:58:29 +0530",
+                "1930-11-18 05:58:29.999999500+05:30",
+            ],
+        ),
+        (
+            Tz::America__St_Johns,
+            [
+                "1930-11-17T20-03:30",
+                "1930-11-17T20:57:37,999999500-03:30",
+                "Mon, 17 Nov 1930 20:57:37 -0330",
+                "1930-11-17 20:57:37.999999500-03:30",
+            ],
+        ),
+    ];
+    for (zone, outputs) in expected {
+        let datetime = instant.with_timezone(&zone);
+        let formats = [
+            NamedFormat::IsoHours,
+            NamedFormat::IsoNanoseconds,
+            NamedFormat::RfcEmail,
+            NamedFormat::Rfc3339Nanoseconds,
+        ];
+        for (format, output) in formats.into_iter().zip(outputs) {
+            assert_eq!(format.format(datetime), output, "{format:?} in {zone}");
+        }
+    }
+
+    let datetime = instant.with_timezone(&Tz::UTC);
+    assert_eq!(NamedFormat::IsoDate.format(datetime), "1930-11-18");
+    assert_eq!(
+        NamedFormat::IsoMinutes.format(datetime),
+        "1930-11-18T00:28+00:00"
+    );
+    assert_eq!(
+        NamedFormat::IsoSeconds.format(datetime),
+        "1930-11-18T00:28:29+00:00"
+    );
+    assert_eq!(NamedFormat::Rfc3339Date.format(datetime), "1930-11-18");
+    assert_eq!(
+        NamedFormat::Rfc3339Seconds.format(datetime),
+        "1930-11-18 00:28:29+00:00"
+    );
+}
+
+#[test]
+fn named_format_precisions() {
+    assert_eq!(
+        NamedFormat::iso_8601("ns"),
+        Some(NamedFormat::IsoNanoseconds)
+    );
+    assert_eq!(NamedFormat::iso_8601("h"), Some(NamedFormat::IsoHours));
+    assert_eq!(NamedFormat::iso_8601("min"), Some(NamedFormat::IsoMinutes));
+    assert_eq!(NamedFormat::iso_8601(""), None);
+    assert_eq!(NamedFormat::iso_8601("x"), None);
+    assert_eq!(
+        NamedFormat::rfc_3339("s"),
+        Some(NamedFormat::Rfc3339Seconds)
+    );
+    assert_eq!(NamedFormat::rfc_3339("hours"), None);
+}
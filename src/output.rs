@@ -0,0 +1,149 @@
+use core::fmt;
+
+/// How a field is padded up to its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// No padding (`-` flag).
+    None,
+    /// Pad with spaces (`_` flag).
+    Space,
+    /// Pad with zeros (`0` flag).
+    Zero,
+    /// Pad with zeros, and put a `+` in front of years too large for
+    /// their field (`+` flag).
+    Sign,
+}
+
+/// Letter case applied to textual fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Convert to upper case (`^` flag).
+    Upper,
+    /// Swap to the opposite case (`#` flag).
+    Opposite,
+    /// Leave the value as produced.
+    Original,
+}
+
+/// A single rendered field, before padding and case conversion are applied.
+///
+/// The [`Display`](fmt::Display) implementation applies the padding and
+/// case rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormattedOutput {
+    /// A number, such as the day of the month.
+    Numeric {
+        value: i64,
+        width: usize,
+        padding: Padding,
+    },
+    /// A piece of text, such as a month name.
+    Text {
+        value: String,
+        case: Case,
+        width: usize,
+        padding: Padding,
+    },
+}
+
+impl Default for FormattedOutput {
+    fn default() -> Self {
+        FormattedOutput::Numeric {
+            value: 0,
+            width: 0,
+            padding: Padding::None,
+        }
+    }
+}
+
+impl fmt::Display for FormattedOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormattedOutput::Numeric {
+                value,
+                width,
+                padding,
+            } => write_number(f, *value, *width, *padding),
+            FormattedOutput::Text {
+                value,
+                case,
+                width,
+                padding,
+            } => write_text(f, value, *case, *width, *padding),
+        }
+    }
+}
+
+/// Writes `value` padded to `width`.
+pub(crate) fn write_number<W: fmt::Write>(
+    out: &mut W,
+    value: i64,
+    width: usize,
+    padding: Padding,
+) -> fmt::Result {
+    let magnitude = value.unsigned_abs();
+    let sign = (value < 0).then_some('-');
+    write_padded_number(out, sign, magnitude, digit_count(magnitude), width, padding)
+}
+
+/// Writes text in `case`, padded to `width` characters.
+pub(crate) fn write_text<W: fmt::Write>(
+    out: &mut W,
+    value: &str,
+    case: Case,
+    width: usize,
+    padding: Padding,
+) -> fmt::Result {
+    let converted;
+    let value = match case {
+        Case::Upper => {
+            converted = value.to_uppercase();
+            &converted
+        }
+        Case::Opposite => {
+            converted = value.to_lowercase();
+            &converted
+        }
+        Case::Original => value,
+    };
+    let fill = match padding {
+        Padding::None => 0,
+        _ => width.saturating_sub(value.chars().count()),
+    };
+    let fill_char = if padding == Padding::Space { ' ' } else { '0' };
+    write_repeated(out, fill_char, fill)?;
+    out.write_str(value)
+}
+
+/// Writes a number the way GNU `date` pads it: the sign counts towards
+/// `width`, zeros go between the sign and the digits, and spaces go before
+/// the sign. `length` is the number of characters `digits` displays as.
+pub(crate) fn write_padded_number<W: fmt::Write>(
+    out: &mut W,
+    sign: Option<char>,
+    digits: impl fmt::Display,
+    length: usize,
+    width: usize,
+    padding: Padding,
+) -> fmt::Result {
+    let fill = width.saturating_sub(usize::from(sign.is_some()) + length);
+    if padding == Padding::Space {
+        write_repeated(out, ' ', fill)?;
+    }
+    if let Some(sign) = sign {
+        out.write_char(sign)?;
+    }
+    if matches!(padding, Padding::Zero | Padding::Sign) {
+        write_repeated(out, '0', fill)?;
+    }
+    write!(out, "{digits}")
+}
+
+/// The number of decimal digits in `value`.
+pub(crate) fn digit_count(value: u64) -> usize {
+    value.checked_ilog10().map_or(1, |log| log as usize + 1)
+}
+
+pub(crate) fn write_repeated<W: fmt::Write>(out: &mut W, c: char, count: usize) -> fmt::Result {
+    (0..count).try_for_each(|_| out.write_char(c))
+}
@@ -0,0 +1,918 @@
+use core::ops::Neg;
+use core::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+
+use crate::compiled::{expansion, CompiledFormat};
+use crate::date_input::zone_abbreviation;
+use crate::directive::{self, Directive, Item, Kind, Modifier};
+use crate::error::{FormatError, ParseError};
+use crate::formatter::Formatter;
+use crate::output::{Case, Padding};
+
+/// Reads `input` according to `plan`, taking names from `formatter`'s
+/// locale.
+pub(crate) fn parse_with(
+    formatter: &Formatter,
+    plan: &CompiledFormat,
+    input: &str,
+) -> Result<DateTime<FixedOffset>, ParseError> {
+    let mut scanner = Scanner {
+        input,
+        pos: 0,
+        formatter,
+        names: None,
+        meridiems: vec![("AM".to_owned(), "PM".to_owned())],
+        fields: Fields::default(),
+    };
+    scanner.items(plan.items(), false, true)?;
+    scanner.fields.resolve()
+}
+
+/// Where a field was read from, for reporting conflicts.
+#[derive(Debug, Clone, Copy)]
+struct Source {
+    specifier: char,
+    offset: usize,
+}
+
+/// The fields read so far. A field given twice keeps the later value.
+#[derive(Debug, Default, Clone)]
+struct Fields {
+    year: Option<i32>,
+    century: Option<i32>,
+    /// Whether `%C` had a minus sign, which is all that tells "-0", the
+    /// century of years -99 to -1, from 0.
+    negative_century: bool,
+    year_of_century: Option<i32>,
+    iso_year: Option<i32>,
+    iso_year_of_century: Option<i32>,
+    quarter: Option<u32>,
+    month: Option<u32>,
+    day: Option<u32>,
+    ordinal: Option<u32>,
+    weekday: Option<(Weekday, Source)>,
+    /// `%U`: weeks starting on Sunday.
+    sunday_week: Option<u32>,
+    /// `%W`: weeks starting on Monday.
+    monday_week: Option<u32>,
+    iso_week: Option<u32>,
+    hour: Option<u32>,
+    hour12: Option<u32>,
+    pm: Option<bool>,
+    minute: Option<u32>,
+    second: Option<u32>,
+    nanosecond: Option<u32>,
+    offset: Option<i32>,
+    timestamp: Option<(i64, Source)>,
+}
+
+impl Fields {
+    /// Combines the fields into a datetime. Missing fields are taken from
+    /// 1970-01-01 00:00:00 UTC.
+    fn resolve(self) -> Result<DateTime<FixedOffset>, ParseError> {
+        let offset =
+            FixedOffset::east_opt(self.offset.unwrap_or(0)).expect("offsets are checked when read");
+        let nanosecond = self.nanosecond.unwrap_or(0);
+
+        if let Some((timestamp, source)) = self.timestamp {
+            return DateTime::from_timestamp(timestamp, nanosecond)
+                .map(|datetime| datetime.with_timezone(&offset))
+                .ok_or(ParseError::OutOfRange {
+                    specifier: source.specifier,
+                    offset: source.offset,
+                });
+        }
+
+        let date = self.date()?;
+        if let Some((weekday, source)) = self.weekday {
+            if date.weekday() != weekday {
+                return Err(ParseError::Conflict {
+                    specifier: source.specifier,
+                    offset: source.offset,
+                });
+            }
+        }
+
+        let hour = match self.hour12 {
+            Some(hour) => hour % 12 + if self.pm == Some(true) { 12 } else { 0 },
+            None => self.hour.unwrap_or(0),
+        };
+        // A leap second is the 60th second, which chrono represents as a
+        // second nanosecond period within second 59.
+        let (second, nanosecond) = match self.second.unwrap_or(0) {
+            60 => (59, nanosecond + 1_000_000_000),
+            second => (second, nanosecond),
+        };
+        let time = NaiveTime::from_hms_nano_opt(hour, self.minute.unwrap_or(0), second, nanosecond)
+            .ok_or(ParseError::InvalidDate)?;
+        offset
+            .from_local_datetime(&NaiveDateTime::new(date, time))
+            .single()
+            .ok_or(ParseError::InvalidDate)
+    }
+
+    fn date(&self) -> Result<NaiveDate, ParseError> {
+        let year = match (self.year, self.century, self.year_of_century) {
+            (Some(year), _, _) => year,
+            (None, Some(century), year) if self.negative_century => {
+                century * 100 - year.unwrap_or(0)
+            }
+            (None, Some(century), year) => century * 100 + year.unwrap_or(0),
+            (None, None, Some(year)) => pivot(year),
+            (None, None, None) => 1970,
+        };
+        let date = if self.month.is_some() || self.day.is_some() || self.quarter.is_some() {
+            let quarter_start = self.quarter.map(|quarter| (quarter - 1) * 3 + 1);
+            let month = self.month.or(quarter_start).unwrap_or(1);
+            NaiveDate::from_ymd_opt(year, month, self.day.unwrap_or(1))
+        } else if let Some(ordinal) = self.ordinal {
+            NaiveDate::from_yo_opt(year, ordinal)
+        } else if let Some(week) = self.iso_week {
+            let year = self
+                .iso_year
+                .or(self.iso_year_of_century.map(pivot))
+                .unwrap_or(year);
+            let weekday = self.weekday.map_or(Weekday::Mon, |(weekday, _)| weekday);
+            NaiveDate::from_isoywd_opt(year, week, weekday)
+        } else if let Some(week) = self.sunday_week {
+            let weekday = self
+                .weekday
+                .map_or(0, |(weekday, _)| weekday.num_days_from_sunday());
+            week_date(year, week, weekday, Weekday::Sun)
+        } else if let Some(week) = self.monday_week {
+            let weekday = self
+                .weekday
+                .map_or(0, |(weekday, _)| weekday.num_days_from_monday());
+            week_date(year, week, weekday, Weekday::Mon)
+        } else {
+            NaiveDate::from_ymd_opt(year, 1, 1)
+        };
+        date.ok_or(ParseError::InvalidDate)
+    }
+}
+
+/// Expands a two-digit year as POSIX does: 69 to 99 are in the 1900s and
+/// 00 to 68 in the 2000s.
+fn pivot(year: i32) -> i32 {
+    if year < 69 {
+        2000 + year
+    } else {
+        1900 + year
+    }
+}
+
+/// The date `days` into week `week` of `year`, where weeks start on
+/// `first` and days before the first such day are in week 0.
+fn week_date(year: i32, week: u32, days: u32, first: Weekday) -> Option<NaiveDate> {
+    let january_first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    let first_week_start =
+        (7 + first.num_days_from_monday() - january_first.weekday().num_days_from_monday()) % 7;
+    let ordinal0 = i64::from(first_week_start) + (i64::from(week) - 1) * 7 + i64::from(days);
+    NaiveDate::from_yo_opt(year, u32::try_from(ordinal0 + 1).ok()?)
+}
+
+/// Localized month and weekday names, full and abbreviated.
+pub(crate) struct Names {
+    /// Month names paired with their number.
+    pub months: Vec<(String, u32)>,
+    pub weekdays: Vec<(String, Weekday)>,
+}
+
+impl Names {
+    /// Loads the names from `formatter`, each full name followed by its
+    /// abbreviation.
+    pub(crate) fn load(formatter: &Formatter, offset: usize) -> Result<Self, ParseError> {
+        let localized = |specifier, datetime: DateTime<Utc>| {
+            formatter
+                .localized(specifier, &datetime)
+                .map_err(|error| ParseError::Format(FormatError::from_icu(offset, error)))
+        };
+        let mut months = Vec::new();
+        for month in 1..=12 {
+            let datetime = Utc.with_ymd_and_hms(2001, month, 1, 0, 0, 0).unwrap();
+            months.push((localized('B', datetime)?, month));
+            months.push((localized('b', datetime)?, month));
+        }
+        let mut weekdays = Vec::new();
+        // 2001 started on a Monday.
+        for day in 1..=7 {
+            let datetime = Utc.with_ymd_and_hms(2001, 1, day, 0, 0, 0).unwrap();
+            weekdays.push((localized('A', datetime)?, datetime.weekday()));
+            weekdays.push((localized('a', datetime)?, datetime.weekday()));
+        }
+        Ok(Names { months, weekdays })
+    }
+}
+
+struct Scanner<'a> {
+    input: &'a str,
+    pos: usize,
+    formatter: &'a Formatter,
+    /// Loaded on the first name directive.
+    names: Option<Names>,
+    /// Morning and afternoon markers `%p` accepts.
+    meridiems: Vec<(String, String)>,
+    fields: Fields,
+}
+
+impl<'a> Scanner<'a> {
+    /// Reads `items` in order. `bounded` tells whether a number directly
+    /// follows them, which limits the digits of years and timestamps, and
+    /// `last` whether the input must end after them.
+    fn items(&mut self, items: &[Item], bounded: bool, last: bool) -> Result<(), ParseError> {
+        for (index, item) in items.iter().enumerate() {
+            match item {
+                Item::Literal(text) => self.literal(text)?,
+                Item::Directive(directive) => {
+                    let next_bounded = match items.get(index + 1) {
+                        Some(Item::Directive(next)) => starts_with_number(next),
+                        Some(Item::Literal(_)) => false,
+                        None => bounded,
+                    };
+                    if next_bounded && self.at_signed_year(directive) {
+                        return self.signed_year(directive, &items[index + 1..], bounded, last);
+                    }
+                    self.directive(directive, next_bounded, false)?;
+                }
+            }
+        }
+        if last && self.pos < self.input.len() {
+            return Err(ParseError::TrailingInput { offset: self.pos });
+        }
+        Ok(())
+    }
+
+    /// Whether `directive` is a year and the input holds a sign for it.
+    fn at_signed_year(&self, directive: &Directive) -> bool {
+        directive::spec(directive.specifier).is_some_and(|spec| matches!(spec.kind, Kind::Year))
+            && self.rest().trim_start_matches(' ').starts_with(['-', '+'])
+    }
+
+    /// Reads a signed year followed directly by a number, then `rest`. A
+    /// year is padded to its usual width with the sign counted in, so
+    /// "-5110807" is year -511 like "-0270807" is year -27, unless the
+    /// rest does not fit, as in "-51100807" for year -5110: the year in
+    /// its usual width is tried first, then with one more digit.
+    fn signed_year(
+        &mut self,
+        directive: &Directive,
+        rest: &[Item],
+        bounded: bool,
+        last: bool,
+    ) -> Result<(), ParseError> {
+        let (pos, fields) = (self.pos, self.fields.clone());
+        let usual = self
+            .directive(directive, true, false)
+            .and_then(|()| self.items(rest, bounded, last));
+        if usual.is_ok() {
+            return usual;
+        }
+        self.pos = pos;
+        self.fields = fields;
+        self.directive(directive, true, true)?;
+        self.items(rest, bounded, last)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Matches literal text. Whitespace in the format matches any amount
+    /// of whitespace, including none.
+    fn literal(&mut self, text: &str) -> Result<(), ParseError> {
+        for (index, expected) in text.char_indices() {
+            if expected.is_whitespace() {
+                self.skip_whitespace();
+            } else if self.rest().starts_with(expected) {
+                self.pos += expected.len_utf8();
+            } else {
+                return Err(ParseError::Mismatch {
+                    expected: text[index..].to_owned(),
+                    offset: self.pos,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches(' ').len();
+    }
+
+    /// Reads `directive`. `bounded` tells whether a number directly follows
+    /// it, and `sign` whether a bounded year then takes one more character
+    /// than usual for its sign.
+    fn directive(
+        &mut self,
+        directive: &Directive,
+        bounded: bool,
+        sign: bool,
+    ) -> Result<(), ParseError> {
+        let specifier = directive.specifier;
+        let source = Source {
+            specifier,
+            offset: self.pos,
+        };
+        let spec = directive::spec(specifier).expect("directive has a known specifier");
+        // Alternative representations are only read when they are the
+        // usual ones, as in a Gregorian locale with ASCII digits.
+        let alternative = match directive.modifier {
+            Some(Modifier::AltDigits) => self.formatter.digits().map(|digits| digits.is_some()),
+            Some(Modifier::Era) if matches!(specifier, 'C' | 'y' | 'Y') => {
+                self.formatter.has_eras()
+            }
+            _ => Ok(false),
+        }
+        .map_err(|error| ParseError::Format(FormatError::from_icu(directive.offset, error)))?;
+        if alternative {
+            return Err(ParseError::Unsupported {
+                specifier,
+                offset: directive.offset,
+            });
+        }
+        match specifier {
+            '%' => return self.literal("%"),
+            'n' | 't' => {
+                self.skip_whitespace();
+                return Ok(());
+            }
+            'a' | 'A' => {
+                let weekday = self.name(directive, |names| &names.weekdays)?;
+                self.fields.weekday = Some((weekday, source));
+                return Ok(());
+            }
+            'b' | 'B' | 'h' => {
+                self.fields.month = Some(self.name(directive, |names| &names.months)?);
+                return Ok(());
+            }
+            'p' | 'P' => {
+                self.fields.pm = Some(self.meridiem(directive)?);
+                return Ok(());
+            }
+            'z' => {
+                self.fields.offset = Some(self.utc_offset(directive)?);
+                return Ok(());
+            }
+            'Z' => {
+                self.fields.offset = Some(self.zone(directive)?);
+                return Ok(());
+            }
+            'c' | 'x' | 'X' => {
+                // %c and %x write a year before 1 in its era, as in
+                // "5/22/8312 BC". Only input with the era's name matches
+                // that layout, so it is tried first.
+                if specifier != 'X' {
+                    let (pos, fields) = (self.pos, self.fields.clone());
+                    let era = self
+                        .localized_items(directive, ERA_SAMPLE_YEAR)
+                        .and_then(|items| self.items(&items, bounded, false));
+                    if era.is_ok() {
+                        self.fields.year = self.fields.year.map(|year| 1 - year);
+                        return Ok(());
+                    }
+                    self.pos = pos;
+                    self.fields = fields;
+                }
+                let items = self.localized_items(directive, SAMPLE_YEAR)?;
+                return self.items(&items, bounded, false);
+            }
+            'N' => {
+                self.fields.nanosecond = Some(self.fraction(directive)?);
+                return Ok(());
+            }
+            's' if directive.epoch_unit.is_some() => {
+                self.epoch(directive, source)?;
+                return Ok(());
+            }
+            // %.f prints nothing for a whole second.
+            'f' if directive.precision.is_some() || self.rest().starts_with('.') => {
+                self.literal(".")?;
+                let limit = directive.precision.unwrap_or(9);
+                let (value, digits): (i64, _) = self.number(directive, Some(limit), false)?;
+                self.fields.nanosecond = Some((value * 10_i64.pow(9 - digits as u32)) as u32);
+                return Ok(());
+            }
+            'f' => return Ok(()),
+            _ => (),
+        }
+        if let Kind::Composite(pattern) = spec.kind {
+            return self.items(expansion(pattern).items(), bounded, false);
+        }
+
+        let limit = digit_limit(directive, bounded).map(|limit| limit + usize::from(sign));
+        let signed = matches!(spec.kind, Kind::Year) || specifier == 's';
+        let (value, _) = self.number(directive, limit, signed)?;
+        let out_of_range = ParseError::OutOfRange {
+            specifier,
+            offset: source.offset,
+        };
+        let in_range = |min: i64, max: i64| {
+            u32::try_from(value)
+                .ok()
+                .filter(|_| (min..=max).contains(&value))
+                .ok_or(out_of_range.clone())
+        };
+        let year = || i32::try_from(value).map_err(|_| out_of_range.clone());
+        match specifier {
+            'C' => {
+                self.fields.century = Some(year()?);
+                self.fields.negative_century = self.input[source.offset..self.pos].contains('-');
+            }
+            'd' | 'e' => self.fields.day = Some(in_range(1, 31)?),
+            'g' => self.fields.iso_year_of_century = Some(in_range(0, 99)? as i32),
+            'G' => self.fields.iso_year = Some(year()?),
+            'H' | 'k' => self.fields.hour = Some(in_range(0, 23)?),
+            'I' | 'l' => self.fields.hour12 = Some(in_range(1, 12)?),
+            'j' => self.fields.ordinal = Some(in_range(1, 366)?),
+            'm' => self.fields.month = Some(in_range(1, 12)?),
+            'M' => self.fields.minute = Some(in_range(0, 59)?),
+            'q' => self.fields.quarter = Some(in_range(1, 4)?),
+            's' => self.fields.timestamp = Some((value, source)),
+            'S' => self.fields.second = Some(in_range(0, 60)?),
+            'u' => {
+                let weekday = Weekday::try_from(in_range(1, 7)? as u8 - 1).unwrap();
+                self.fields.weekday = Some((weekday, source));
+            }
+            'U' => self.fields.sunday_week = Some(in_range(0, 53)?),
+            'V' => self.fields.iso_week = Some(in_range(1, 53)?),
+            'w' => {
+                let weekday = Weekday::try_from(in_range(0, 6)? as u8).unwrap().pred();
+                self.fields.weekday = Some((weekday, source));
+            }
+            'W' => self.fields.monday_week = Some(in_range(0, 53)?),
+            'y' => self.fields.year_of_century = Some(in_range(0, 99)? as i32),
+            'Y' => self.fields.year = Some(year()?),
+            _ => unreachable!("'{specifier}' is not numeric"),
+        }
+        Ok(())
+    }
+
+    /// Reads a number of at most `limit` characters, counting leading
+    /// spaces and the sign as `%_5d` and `%+6Y` lay them out. Returns the
+    /// value and the number of digits read; a value that does not fit in
+    /// `T` is out of range.
+    fn number<T: FromStr + Neg<Output = T>>(
+        &mut self,
+        directive: &Directive,
+        limit: Option<usize>,
+        signed: bool,
+    ) -> Result<(T, usize), ParseError> {
+        let start = self.pos;
+        let bytes = self.input.as_bytes();
+        let fits = |end: usize| limit.is_none_or(|limit| end - start < limit);
+        let mut end = start;
+        while end < bytes.len() && bytes[end] == b' ' && fits(end + 1) {
+            end += 1;
+        }
+        let negative = signed && bytes.get(end) == Some(&b'-');
+        if signed && matches!(bytes.get(end), Some(b'-' | b'+')) {
+            end += 1;
+        }
+        let digits_start = end;
+        while end < bytes.len() && bytes[end].is_ascii_digit() && fits(end) {
+            end += 1;
+        }
+        if digits_start == end {
+            return Err(ParseError::InvalidField {
+                specifier: directive.specifier,
+                offset: start,
+            });
+        }
+        let magnitude: T =
+            self.input[digits_start..end]
+                .parse()
+                .map_err(|_| ParseError::OutOfRange {
+                    specifier: directive.specifier,
+                    offset: start,
+                })?;
+        self.pos = end;
+        Ok((
+            if negative { -magnitude } else { magnitude },
+            end - digits_start,
+        ))
+    }
+
+    /// Reads `%N` in nanoseconds. The digits are the start of the fraction
+    /// of a second, laid out to the width as rendering does: only the first
+    /// 9 count and any after them must be zeros, and with `_` spaces may
+    /// stand in for trailing zeros.
+    fn fraction(&mut self, directive: &Directive) -> Result<u32, ParseError> {
+        let (width, padding) = directive::fraction_layout(directive);
+        let start = self.pos;
+        // Only zero padding writes more than 9 digits.
+        let limit = match padding {
+            Padding::Zero | Padding::Sign => width,
+            Padding::None | Padding::Space => width.min(9),
+        };
+        let length = self
+            .rest()
+            .bytes()
+            .take(limit)
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if length == 0 {
+            return Err(ParseError::InvalidField {
+                specifier: directive.specifier,
+                offset: start,
+            });
+        }
+        let (digits, rest) = self.rest()[..length].split_at(length.min(9));
+        if rest.bytes().any(|b| b != b'0') {
+            return Err(ParseError::OutOfRange {
+                specifier: directive.specifier,
+                offset: start,
+            });
+        }
+        let value: u32 = digits.parse().expect("at most 9 ASCII digits fit in a u32");
+        self.pos += length;
+        if padding == Padding::Space {
+            self.pos += self
+                .rest()
+                .bytes()
+                .take(width.saturating_sub(length))
+                .take_while(|&b| b == b' ')
+                .count();
+        }
+        Ok(value * 10_u32.pow((9 - digits.len()) as u32))
+    }
+
+    /// Reads `%{epoch:ms}` and its siblings, with the fraction of the unit
+    /// that follows when a precision is given.
+    fn epoch(&mut self, directive: &Directive, source: Source) -> Result<(), ParseError> {
+        let unit = directive
+            .epoch_unit
+            .expect("directive counts time since the epoch");
+        let out_of_range = ParseError::OutOfRange {
+            specifier: directive.specifier,
+            offset: source.offset,
+        };
+        // Like rendering, count in i128: nanoseconds since the epoch
+        // overflow an i64 after the year 2262.
+        let (count, _): (i128, _) = self.number(directive, None, true)?;
+        let mut nanoseconds = count
+            .checked_mul(unit.nanoseconds().into())
+            .ok_or(out_of_range.clone())?;
+        if let Some(precision) = directive.precision {
+            self.literal(".")?;
+            let (value, digits): (i128, _) = self.number(directive, Some(precision), false)?;
+            nanoseconds = nanoseconds
+                .checked_add(value * 10_i128.pow((unit.fraction_digits() - digits) as u32))
+                .ok_or(out_of_range.clone())?;
+        }
+        let seconds =
+            i64::try_from(nanoseconds.div_euclid(1_000_000_000)).map_err(|_| out_of_range)?;
+        self.fields.timestamp = Some((seconds, source));
+        self.fields.nanosecond = Some(nanoseconds.rem_euclid(1_000_000_000) as u32);
+        Ok(())
+    }
+
+    /// Reads the longest of the locale's month or weekday names, ignoring
+    /// case.
+    fn name<T: Copy>(
+        &mut self,
+        directive: &Directive,
+        list: impl Fn(&Names) -> &Vec<(String, T)>,
+    ) -> Result<T, ParseError> {
+        self.skip_spaces();
+        let rest = self.rest();
+        let (length, value) =
+            longest_match(rest, list(self.names(directive)?)).ok_or(ParseError::InvalidField {
+                specifier: directive.specifier,
+                offset: self.pos,
+            })?;
+        self.pos += length;
+        Ok(value)
+    }
+
+    fn names(&mut self, directive: &Directive) -> Result<&Names, ParseError> {
+        if self.names.is_none() {
+            self.names = Some(Names::load(self.formatter, directive.offset)?);
+        }
+        Ok(self.names.as_ref().expect("names were just loaded"))
+    }
+
+    /// Reads a morning or afternoon marker, ignoring case. Returns whether
+    /// it is the afternoon.
+    fn meridiem(&mut self, directive: &Directive) -> Result<bool, ParseError> {
+        self.skip_spaces();
+        let markers: Vec<_> = self
+            .meridiems
+            .iter()
+            .flat_map(|(am, pm)| [(am.clone(), false), (pm.clone(), true)])
+            .collect();
+        let (length, pm) =
+            longest_match(self.rest(), &markers).ok_or(ParseError::InvalidField {
+                specifier: directive.specifier,
+                offset: self.pos,
+            })?;
+        self.pos += length;
+        Ok(pm)
+    }
+
+    /// Reads a numeric UTC offset in any of the layouts of `%z`, `%:z`,
+    /// `%::z` and `%:::z`, or `Z` for UTC. Returns it in seconds.
+    fn utc_offset(&mut self, directive: &Directive) -> Result<i32, ParseError> {
+        self.skip_spaces();
+        let start = self.pos;
+        let invalid = ParseError::InvalidField {
+            specifier: directive.specifier,
+            offset: start,
+        };
+        let rest = self.rest();
+        if rest.starts_with(['Z', 'z']) {
+            self.pos += 1;
+            return Ok(0);
+        }
+        let sign = match rest.as_bytes().first() {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Err(invalid),
+        };
+        self.pos += 1;
+        let first = self.digits();
+        // %-z and %_z drop the leading zeros of hhmm, so "+53" is minutes.
+        let unpadded = directive.colons == 0
+            && matches!(directive.padding, Some(Padding::None | Padding::Space))
+            && !self.rest().starts_with(':');
+        let (hours, mut minutes, mut seconds) = match first.len() {
+            1 | 2 if unpadded => ("0", first, "0"),
+            1 | 2 => (first, "0", "0"),
+            3 | 4 => (&first[..first.len() - 2], &first[first.len() - 2..], "0"),
+            5 | 6 => (
+                &first[..first.len() - 4],
+                &first[first.len() - 4..first.len() - 2],
+                &first[first.len() - 2..],
+            ),
+            _ => {
+                self.pos = start;
+                return Err(invalid);
+            }
+        };
+        if first.len() <= 2 && self.rest().starts_with(':') {
+            self.pos += 1;
+            minutes = self.digits();
+            if self.rest().starts_with(':') {
+                self.pos += 1;
+                seconds = self.digits();
+            }
+        }
+        if minutes.len() > 2 || seconds.len() > 2 || minutes.is_empty() || seconds.is_empty() {
+            self.pos = start;
+            return Err(invalid);
+        }
+        let [hours, minutes, seconds] =
+            [hours, minutes, seconds].map(|field| field.parse::<i32>().unwrap());
+        let total = sign * (hours * 3600 + minutes * 60 + seconds);
+        if minutes > 59 || seconds > 59 || FixedOffset::east_opt(total).is_none() {
+            return Err(ParseError::OutOfRange {
+                specifier: directive.specifier,
+                offset: start,
+            });
+        }
+        Ok(total)
+    }
+
+    fn digits(&mut self) -> &'a str {
+        let rest = self.rest();
+        let length = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        self.pos += length;
+        &rest[..length]
+    }
+
+    /// Reads `%Z`: a numeric offset or one of the zone abbreviations GNU
+    /// `date` understands, such as `UTC` or `CEST`.
+    fn zone(&mut self, directive: &Directive) -> Result<i32, ParseError> {
+        self.skip_spaces();
+        if self.rest().starts_with(['+', '-']) {
+            return self.utc_offset(directive);
+        }
+        let rest = self.rest();
+        let word = &rest[..rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .len()];
+        let hours =
+            zone_abbreviation(&word.to_ascii_lowercase()).ok_or(ParseError::InvalidField {
+                specifier: directive.specifier,
+                offset: self.pos,
+            })?;
+        self.pos += word.len();
+        Ok(hours * 3600)
+    }
+
+    /// Works out the items of the locale's `%c`, `%x` or `%X` by rendering
+    /// a sample afternoon and morning on November 22 of `year` and
+    /// recognising the fields in them. For a year before 1, `%Y` stands for
+    /// the year in the era before the common era.
+    fn localized_items(
+        &mut self,
+        directive: &Directive,
+        year: i32,
+    ) -> Result<Vec<Item>, ParseError> {
+        let specifier = directive.specifier;
+        let unsupported = ParseError::Unsupported {
+            specifier,
+            offset: directive.offset,
+        };
+        let render = |hour| {
+            let datetime = Utc.with_ymd_and_hms(year, 11, 22, hour, 44, 55).unwrap();
+            self.formatter
+                .localized(specifier, &datetime)
+                .map_err(|error| ParseError::Format(FormatError::from_icu(directive.offset, error)))
+        };
+        let (afternoon, morning) = (render(13)?, render(1)?);
+        // Only ASCII digits are read, as with %O.
+        if afternoon
+            .chars()
+            .any(|c| c.is_numeric() && !c.is_ascii_digit())
+        {
+            return Err(unsupported);
+        }
+        // Names are loaded full name first, so the sample's come in the
+        // order %B, %b, %A, %a.
+        let names = self.names(directive)?;
+        let sample_names: Vec<_> = names
+            .months
+            .iter()
+            .filter(|&&(_, month)| month == 11)
+            .map(|(name, _)| name.clone())
+            .chain(
+                names
+                    .weekdays
+                    .iter()
+                    .filter(|&&(_, weekday)| weekday == Weekday::Tue)
+                    .map(|(name, _)| name.clone()),
+            )
+            .zip(['B', 'b', 'A', 'a'])
+            .collect();
+        let afternoon = sample_tokens(&afternoon, &sample_names);
+        let morning = sample_tokens(&morning, &sample_names);
+        if afternoon.len() != morning.len() {
+            return Err(unsupported);
+        }
+
+        let field = |specifier| {
+            Item::Directive(Directive {
+                specifier,
+                case: Case::Original,
+                padding: None,
+                width: None,
+                precision: None,
+                epoch_unit: None,
+                colons: 0,
+                modifier: None,
+                offset: directive.offset,
+            })
+        };
+        let shown_year = if year > 0 { year } else { 1 - year };
+        let (full_year, short_year) = (shown_year.to_string(), format!("{:02}", shown_year % 100));
+        let mut items = Vec::new();
+        let mut has_field = false;
+        for (token, other) in afternoon.into_iter().zip(morning) {
+            let item = match token {
+                SampleToken::Name(specifier) => field(specifier),
+                SampleToken::Digits(digits) => field(match digits.as_str() {
+                    digits if digits == full_year => 'Y',
+                    digits if digits == short_year => 'y',
+                    "11" => 'm',
+                    "22" => 'd',
+                    "13" => 'H',
+                    "1" | "01" => 'I',
+                    "44" => 'M',
+                    "55" => 'S',
+                    _ => return Err(unsupported),
+                }),
+                SampleToken::Text(pm) => match other {
+                    SampleToken::Text(am) if am != pm => {
+                        self.meridiems.push((am, pm));
+                        field('p')
+                    }
+                    _ => Item::Literal(pm),
+                },
+            };
+            match (&item, items.last_mut()) {
+                (Item::Literal(text), Some(Item::Literal(previous))) => previous.push_str(text),
+                _ => {
+                    has_field |= matches!(item, Item::Directive(_));
+                    items.push(item);
+                }
+            }
+        }
+        if !has_field {
+            return Err(unsupported);
+        }
+        Ok(items)
+    }
+}
+
+/// The year of the samples `%c`, `%x` and `%X` are recognised in. Its
+/// November 22 is a Tuesday.
+const SAMPLE_YEAR: i32 = 2033;
+
+/// The year of the samples for a year before 1: 1968 BC, 4000 years
+/// before [`SAMPLE_YEAR`], so that its November 22 is a Tuesday too.
+const ERA_SAMPLE_YEAR: i32 = -1967;
+
+/// A piece of a sample rendering of `%c`, `%x` or `%X`.
+#[derive(Debug, PartialEq, Eq)]
+enum SampleToken {
+    /// A month or weekday name, as the specifier that renders it.
+    Name(char),
+    Digits(String),
+    Text(String),
+}
+
+/// Splits `sample` into names, runs of digits, runs of letters and other
+/// characters.
+fn sample_tokens(sample: &str, names: &[(String, char)]) -> Vec<SampleToken> {
+    let mut tokens = Vec::new();
+    let mut rest = sample;
+    while let Some(c) = rest.chars().next() {
+        let length = if let Some((length, specifier)) = longest_match(rest, names) {
+            tokens.push(SampleToken::Name(specifier));
+            length
+        } else {
+            let run: fn(char) -> bool = if c.is_ascii_digit() {
+                |c| c.is_ascii_digit()
+            } else if c.is_alphabetic() {
+                |c| c.is_alphabetic()
+            } else {
+                |_| false
+            };
+            let length = rest
+                .char_indices()
+                .find(|&(_, next)| !run(next))
+                .map_or(rest.len(), |(index, _)| index)
+                .max(c.len_utf8());
+            let text = rest[..length].to_owned();
+            tokens.push(if c.is_ascii_digit() {
+                SampleToken::Digits(text)
+            } else {
+                SampleToken::Text(text)
+            });
+            length
+        };
+        rest = &rest[length..];
+    }
+    tokens
+}
+
+/// Finds the longest entry of `candidates` that `input` starts with,
+/// ignoring case, and returns the length it takes up in `input`.
+pub(crate) fn longest_match<T: Copy>(
+    input: &str,
+    candidates: &[(String, T)],
+) -> Option<(usize, T)> {
+    candidates
+        .iter()
+        .filter(|(name, _)| !name.is_empty())
+        .filter_map(|(name, value)| Some((prefix_ignoring_case(input, name)?, *value)))
+        .max_by_key(|&(length, _)| length)
+}
+
+/// The length of the start of `input` that equals `prefix` ignoring case.
+fn prefix_ignoring_case(input: &str, prefix: &str) -> Option<usize> {
+    let mut input_chars = input.char_indices();
+    for expected in prefix.chars() {
+        let (_, actual) = input_chars.next()?;
+        if !actual.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(input_chars.next().map_or(input.len(), |(index, _)| index))
+}
+
+/// Whether `directive` renders something that starts with a digit, so
+/// that a number before it must stop at its usual width.
+fn starts_with_number(directive: &Directive) -> bool {
+    directive::spec(directive.specifier)
+        .is_some_and(|spec| matches!(spec.kind, Kind::Numeric | Kind::Year | Kind::Composite(_)))
+        || matches!(directive.specifier, 'c' | 'x' | 'X')
+}
+
+/// The most characters a number may take up: the field's usual width or
+/// its explicit width, whichever is larger. Years and timestamps have no
+/// limit unless another number follows them directly.
+fn digit_limit(directive: &Directive, bounded: bool) -> Option<usize> {
+    let usual = match directive.specifier {
+        's' => return None,
+        'Y' | 'G' | 'C' if !bounded => return None,
+        'Y' | 'G' => 4,
+        'j' => 3,
+        'q' | 'u' | 'w' => 1,
+        _ => 2,
+    };
+    Some(directive.width.map_or(usual, |width| width.max(usual)))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,296 @@
+use chrono::{FixedOffset, TimeZone, Timelike};
+
+use icu::locid::locale;
+
+use crate::{format, FormatError, Formatter, ParseError};
+
+#[test]
+fn parse_round_trips() {
+    let datetimes = [
+        FixedOffset::east_opt(-12600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+            .unwrap()
+            + chrono::Duration::nanoseconds(123_456_789),
+        FixedOffset::east_opt(19800)
+            .unwrap()
+            .with_ymd_and_hms(1999, 1, 3, 0, 0, 9)
+            .unwrap(),
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2049, 3, 15, 12, 30, 0)
+            .unwrap(),
+    ];
+    let formats = [
+        "%Y-%m-%dT%H:%M:%S.%N%:z",
+        "%Y%m%d%H%M%S%N%z",
+        "%s.%N %::z",
+        "%A, %B %e %Y %l:%M:%S.%N %p %z",
+        "%^a %^b %_d %k:%M:%S,%N %Y W%V %:::z",
+        "%-d/%-m/%Y %-H:%-M:%-S %N %Z",
+        "%G-W%V-%u %T.%N %z",
+        "%Y %j %T %N %z",
+        "%Y %U %w %T %N %z",
+        "%Y %W %a %T %N %z",
+        "%F %r %N %z",
+        "%c %N %z",
+        "%x %X %N %z",
+        "%C%y%m%d %T %N %z",
+    ];
+    for datetime in datetimes {
+        for format_string in formats {
+            let text = format(format_string, datetime);
+            assert_eq!(
+                crate::parse(&text, format_string),
+                Ok(datetime),
+                "{text:?} as {format_string:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn parse_negative_years() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    for (year, month) in [
+        (-5110, 10),
+        (-5110, 8),
+        (-2000, 8),
+        (-511, 8),
+        (-100, 8),
+        (-27, 8),
+        (-1, 8),
+    ] {
+        let datetime = utc.with_ymd_and_hms(year, month, 7, 10, 20, 30).unwrap();
+        for format_string in [
+            "%Y%m%d%H%M%S",
+            "%C%y%m%d%H%M%S",
+            "%G%V%u %T",
+            "%C %y %m %d %T",
+            "%EC%Ey-%m-%d %T",
+        ] {
+            let text = format(format_string, datetime);
+            assert_eq!(
+                crate::parse(&text, format_string),
+                Ok(datetime),
+                "{text:?} as {format_string:?}"
+            );
+        }
+    }
+    // "-0" is the century of years -99 to -1.
+    assert_eq!(
+        format("%C|%y", utc.with_ymd_and_hms(-27, 1, 1, 0, 0, 0).unwrap()),
+        "-0|27"
+    );
+    assert_eq!(
+        crate::parse("-027", "%C%y"),
+        Ok(utc.with_ymd_and_hms(-27, 1, 1, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn parse_locale_names() {
+    let datetime = FixedOffset::east_opt(3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap();
+    for locale in [
+        locale!("fr-FR"),
+        locale!("de-DE"),
+        locale!("ja-JP"),
+        locale!("ko"),
+    ] {
+        let formatter = Formatter::new(locale.clone());
+        for format_string in [
+            "%A %d %B %Y %T %z",
+            "%a %d %b %Y %T %z",
+            "%c %z",
+            "%x %X %z",
+        ] {
+            let text = formatter.format(format_string, datetime);
+            assert_eq!(
+                formatter.parse(&text, format_string),
+                Ok(datetime),
+                "{text:?} as {format_string:?} in {locale}"
+            );
+        }
+    }
+    let german = Formatter::new(locale!("de-DE"));
+    assert_eq!(
+        german.parse(
+            "SAMSTAG, 14. dezember 2024 15:04 +0100",
+            "%A, %d. %B %Y %R %z"
+        ),
+        Ok(datetime.with_second(0).unwrap())
+    );
+}
+
+#[test]
+fn parse_localized_years_before_common_era() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    for locale in [locale!("en"), locale!("de-DE"), locale!("ja-JP")] {
+        let formatter = Formatter::new(locale.clone());
+        for year in [-8311, -1757, 0, 2024] {
+            let datetime = utc.with_ymd_and_hms(year, 5, 22, 15, 4, 5).unwrap();
+            let midnight = utc.with_ymd_and_hms(year, 5, 22, 0, 0, 0).unwrap();
+            for (format_string, expected) in
+                [("%x", midnight), ("%c", datetime), ("%x %X", datetime)]
+            {
+                let text = formatter.format(format_string, datetime);
+                assert_eq!(
+                    formatter.parse(&text, format_string),
+                    Ok(expected),
+                    "{text:?} as {format_string:?} in {locale}"
+                );
+            }
+        }
+    }
+    let english = Formatter::new(locale!("en"));
+    assert_eq!(
+        english.parse("5/22/8312 BC", "%x"),
+        Ok(utc.with_ymd_and_hms(-8311, 5, 22, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn parse_lenient_fields() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let expected = utc.with_ymd_and_hms(2024, 3, 5, 7, 8, 0).unwrap();
+    for (input, format_string) in [
+        ("2024-3-5 7:08", "%Y-%m-%d %H:%M"),
+        ("2024- 3- 5  7:08", "%Y-%_m-%_d %k:%M"),
+        ("05.03.24 07h08", "%d.%m.%y %Hh%M"),
+        ("Tue  mar 5 07:08:00 UTC 2024", "%a %b %e %T %Z %Y"),
+        ("7:08am 2024-065", "%I:%M%p %Y-%j"),
+        ("2024-03-05T07:08Z", "%FT%R%z"),
+        ("1709622480", "%s"),
+        ("2024 Q1 03 05\t07 08", "%Y Q%q %m %d%n%H %M"),
+    ] {
+        assert_eq!(
+            crate::parse(input, format_string),
+            Ok(expected),
+            "{input:?} as {format_string:?}"
+        );
+    }
+    assert_eq!(
+        crate::parse("-0044-03-15", "%F"),
+        Ok(utc.with_ymd_and_hms(-44, 3, 15, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        crate::parse("+12345-03-15", "%F"),
+        Ok(utc.with_ymd_and_hms(12345, 3, 15, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        crate::parse("14:00", "%H:%M"),
+        Ok(utc.with_ymd_and_hms(1970, 1, 1, 14, 0, 0).unwrap())
+    );
+    assert_eq!(
+        crate::parse("2016-12-31 23:59:60", "%F %T").map(|datetime| datetime.to_rfc3339()),
+        Ok("2016-12-31T23:59:60+00:00".to_owned())
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let cases = [
+        (
+            "2024-13-01",
+            "%F",
+            ParseError::OutOfRange {
+                specifier: 'm',
+                offset: 5,
+            },
+        ),
+        (
+            "2024/12/01",
+            "%F",
+            ParseError::Mismatch {
+                expected: "-".to_owned(),
+                offset: 4,
+            },
+        ),
+        (
+            "Dec x",
+            "%b %d",
+            ParseError::InvalidField {
+                specifier: 'd',
+                offset: 4,
+            },
+        ),
+        (
+            "Fri 2024-12-14",
+            "%a %F",
+            ParseError::Conflict {
+                specifier: 'a',
+                offset: 0,
+            },
+        ),
+        (
+            "2024-12-14 extra",
+            "%F",
+            ParseError::TrailingInput { offset: 10 },
+        ),
+        ("2024-02-30", "%F", ParseError::InvalidDate),
+        (
+            "12:00 +2500",
+            "%R %z",
+            ParseError::OutOfRange {
+                specifier: 'z',
+                offset: 6,
+            },
+        ),
+        (
+            "12:00 XYZ",
+            "%R %Z",
+            ParseError::InvalidField {
+                specifier: 'Z',
+                offset: 6,
+            },
+        ),
+        (
+            "2024",
+            "%Y %Q",
+            ParseError::Format(FormatError::UnknownSpecifier {
+                specifier: 'Q',
+                offset: 4,
+            }),
+        ),
+    ];
+    for (input, format_string, error) in cases {
+        assert_eq!(crate::parse(input, format_string), Err(error), "{input:?}");
+    }
+}
+
+#[test]
+fn wide_fractions_parse_back() {
+    let datetime = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 14, 15, 4, 5)
+        .unwrap()
+        + chrono::Duration::nanoseconds(123_456_789);
+    for width in [20, 27, 28, 30] {
+        let format_string = format!("%F %T.%{width}N %z");
+        let text = format(&format_string, datetime);
+        assert_eq!(
+            crate::parse(&text, &format_string),
+            Ok(datetime),
+            "{text:?} as {format_string:?}"
+        );
+    }
+    assert_eq!(
+        crate::parse(&format("%30N", datetime), "%30N"),
+        Ok(chrono::DateTime::from_timestamp(0, 123_456_789)
+            .unwrap()
+            .fixed_offset())
+    );
+    assert_eq!(
+        crate::parse(
+            "2024-12-14 15:04:05.123456789000000000001 +0000",
+            "%F %T.%21N %z"
+        ),
+        Err(ParseError::OutOfRange {
+            specifier: 'N',
+            offset: 20
+        })
+    );
+}
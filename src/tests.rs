@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception, unused_imports)]
 mod tests {
     use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 
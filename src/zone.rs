@@ -0,0 +1,145 @@
+use std::env;
+
+use core::fmt;
+
+use chrono::{FixedOffset, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+/// Resolves the time zone to display dates in from the environment.
+///
+/// `TZ` is used when set, as an IANA name with an optional leading `:`
+/// (`Europe/Berlin`, `:Asia/Tokyo`); an empty `TZ` is UTC. Without `TZ`,
+/// the system's configured zone is used. Returns `None` if the zone is
+/// not in the bundled tz database, for example a POSIX rule such as
+/// `EST5EDT,M3.2.0,M11.1.0`; [`parse_posix_zone`] reads the standard time
+/// of those.
+pub fn zone_from_env() -> Option<Tz> {
+    match env::var("TZ") {
+        Ok(name) => parse_zone(&name),
+        Err(_) => parse_zone(&iana_time_zone::get_timezone().ok()?),
+    }
+}
+
+/// Looks up an IANA zone by name, accepting a leading `:` as in `TZ`. An
+/// empty name is UTC.
+pub fn parse_zone(name: &str) -> Option<Tz> {
+    let name = name.strip_prefix(':').unwrap_or(name);
+    if name.is_empty() {
+        return Some(Tz::UTC);
+    }
+    name.parse().ok()
+}
+
+#[cfg(test)]
+mod tests;
+
+/// A time zone from the standard time of a POSIX `TZ` string: a fixed
+/// offset that `%Z` shows as the abbreviation given with it.
+///
+/// ```
+/// use chrono::TimeZone;
+///
+/// let eastern = datefmt::parse_posix_zone("EST5EDT,M3.2.0,M11.1.0").unwrap();
+/// let datetime = eastern.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+/// assert_eq!(datefmt::format("%T %Z %z", datetime), "12:00:00 EST -0500");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixZone {
+    abbreviation: String,
+    offset: FixedOffset,
+}
+
+impl PosixZone {
+    /// The abbreviation of the zone's standard time, such as `EST`.
+    pub fn abbreviation(&self) -> &str {
+        &self.abbreviation
+    }
+}
+
+impl Offset for PosixZone {
+    fn fix(&self) -> FixedOffset {
+        self.offset
+    }
+}
+
+impl fmt::Display for PosixZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.abbreviation)
+    }
+}
+
+impl TimeZone for PosixZone {
+    type Offset = PosixZone;
+
+    fn from_offset(offset: &PosixZone) -> Self {
+        offset.clone()
+    }
+
+    fn offset_from_local_date(&self, _local: &NaiveDate) -> LocalResult<PosixZone> {
+        LocalResult::Single(self.clone())
+    }
+
+    fn offset_from_local_datetime(&self, _local: &NaiveDateTime) -> LocalResult<PosixZone> {
+        LocalResult::Single(self.clone())
+    }
+
+    fn offset_from_utc_date(&self, _utc: &NaiveDate) -> PosixZone {
+        self.clone()
+    }
+
+    fn offset_from_utc_datetime(&self, _utc: &NaiveDateTime) -> PosixZone {
+        self.clone()
+    }
+}
+
+/// Reads the standard time of a POSIX `TZ` string such as `UTC0`, `JST-9`
+/// or `<+0330>-3:30`: an abbreviation of at least three letters, or any in
+/// `<>`, followed by the hours west of UTC as `[+|-]hh[:mm[:ss]]`.
+/// Daylight saving time rules after it are ignored. Like the C library, a
+/// missing or invalid offset is UTC, so `Foo/Bar` is UTC named `Foo`.
+/// Returns `None` without a valid abbreviation.
+pub fn parse_posix_zone(tz: &str) -> Option<PosixZone> {
+    let tz = tz.strip_prefix(':').unwrap_or(tz);
+    let (abbreviation, rest) = match tz.strip_prefix('<') {
+        Some(quoted) => quoted.split_once('>')?,
+        None => tz.split_at(
+            tz.find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tz.len()),
+        ),
+    };
+    if abbreviation.len() < 3 {
+        return None;
+    }
+    Some(PosixZone {
+        abbreviation: abbreviation.to_owned(),
+        offset: posix_offset(rest).unwrap_or(FixedOffset::east_opt(0).unwrap()),
+    })
+}
+
+/// Reads a POSIX offset, `[+|-]hh[:mm[:ss]]` west of UTC, from the start
+/// of `text`.
+fn posix_offset(text: &str) -> Option<FixedOffset> {
+    let (west, text) = match text.as_bytes().first()? {
+        b'-' => (false, &text[1..]),
+        b'+' => (true, &text[1..]),
+        _ => (true, text),
+    };
+    let end = text
+        .find(|c: char| !c.is_ascii_digit() && c != ':')
+        .unwrap_or(text.len());
+    let mut seconds = 0;
+    for (index, part) in text[..end].split(':').enumerate() {
+        let value: i32 = part.parse().ok()?;
+        let (unit, max) = match index {
+            0 => (3600, 24),
+            1 => (60, 59),
+            2 => (1, 59),
+            _ => return None,
+        };
+        if part.len() > 2 || value > max {
+            return None;
+        }
+        seconds += value * unit;
+    }
+    FixedOffset::west_opt(if west { seconds } else { -seconds })
+}
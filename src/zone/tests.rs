@@ -0,0 +1,57 @@
+use chrono::{FixedOffset, Offset, TimeZone};
+
+use crate::{format, parse_posix_zone, parse_zone, Tz};
+
+#[test]
+fn zone_abbreviations_follow_dst() {
+    let berlin: Tz = "Europe/Berlin".parse().unwrap();
+    let winter = berlin.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+    let summer = berlin.with_ymd_and_hms(2024, 7, 15, 12, 0, 0).unwrap();
+    assert_eq!(format("%Z %z", winter), "CET +0100");
+    assert_eq!(format("%Z %z", summer), "CEST +0200");
+
+    let los_angeles: Tz = "America/Los_Angeles".parse().unwrap();
+    let datetime = los_angeles.with_ymd_and_hms(2024, 7, 15, 12, 0, 0).unwrap();
+    assert_eq!(format("%Z %#Z %_10Z", datetime), "PDT pdt        PDT");
+}
+
+#[test]
+fn zone_name_of_fixed_offsets_and_utc() {
+    let datetime = FixedOffset::east_opt(5 * 3600 + 30 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert_eq!(format("%Z", datetime), "+05:30");
+    assert_eq!(format("%Z", datetime.with_timezone(&chrono::Utc)), "UTC");
+    assert_eq!(format("%Z", datetime.with_timezone(&Tz::UTC)), "UTC");
+}
+
+#[test]
+fn zone_names() {
+    assert_eq!(parse_zone("Europe/Berlin"), Some(Tz::Europe__Berlin));
+    assert_eq!(parse_zone(":Asia/Tokyo"), Some(Tz::Asia__Tokyo));
+    assert_eq!(parse_zone("UTC"), Some(Tz::UTC));
+    assert_eq!(parse_zone(""), Some(Tz::UTC));
+    assert_eq!(parse_zone("EST5EDT,M3.2.0,M11.1.0"), None);
+}
+
+#[test]
+fn posix_zones() {
+    let zone = |tz| parse_posix_zone(tz).map(|zone| (zone.abbreviation().to_owned(), zone.fix()));
+    let east = |seconds| FixedOffset::east_opt(seconds).unwrap();
+    assert_eq!(zone("UTC0"), Some(("UTC".to_owned(), east(0))));
+    assert_eq!(zone(":UTC0"), Some(("UTC".to_owned(), east(0))));
+    assert_eq!(
+        zone("EST5EDT,M3.2.0,M11.1.0"),
+        Some(("EST".to_owned(), east(-5 * 3600)))
+    );
+    assert_eq!(zone("JST-9"), Some(("JST".to_owned(), east(9 * 3600))));
+    assert_eq!(
+        zone("<+0330>-3:30"),
+        Some(("+0330".to_owned(), east(3 * 3600 + 30 * 60)))
+    );
+    assert_eq!(zone("Foo/Bar"), Some(("Foo".to_owned(), east(0))));
+    assert_eq!(zone("XYZ99"), Some(("XYZ".to_owned(), east(0))));
+    assert_eq!(zone("UT0"), None);
+    assert_eq!(zone("<+03"), None);
+}